@@ -1,29 +1,388 @@
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::convert::TryFrom;
+use std::env;
+use std::fmt;
+use std::io::{self, Write};
+use std::process;
 
 const INPUT: &str = include_str!("../input/day01.txt");
 
 fn main() {
-    println!("Part 1: {}", part1(INPUT));
-    println!("Part 2: {}", part2(INPUT));
+    // malformed lines are fatal unless asked to skip them
+    let changes = if env::args().any(|arg| arg == "--lenient") {
+        parse_lenient(INPUT)
+    } else {
+        match parse_strict(INPUT) {
+            Ok(changes) => changes,
+            Err(bad_lines) => {
+                for bad in bad_lines {
+                    eprintln!("error: {}", bad);
+                }
+                process::exit(1);
+            }
+        }
+    };
+
+    let passes = requested_passes();
+    let result = if env::args().any(|arg| arg == "--csv") {
+        write_series(&changes, passes)
+    } else {
+        let with_report = env::args().any(|arg| arg == "--report");
+        print_answers(&changes, with_report, passes)
+    };
+
+    if let Err(e) = result {
+        eprintln!("error: {}", e);
+        process::exit(1);
+    }
+}
+
+/// The most passes a report follows, since it keeps every frequency on the way
+const MAX_PASSES: usize = 1000;
+
+/// `--passes=K` follows the frequency for K passes in the report and the
+/// CSV, instead of just far enough to see the first repeat
+fn requested_passes() -> Option<usize> {
+    let arg = env::args().find_map(|arg| arg.strip_prefix("--passes=").map(String::from))?;
+
+    match arg.parse::<usize>() {
+        Ok(k) if k > MAX_PASSES => {
+            eprintln!("warning: following only {} passes", MAX_PASSES);
+            Some(k)
+        }
+        Ok(k) => Some(k),
+        Err(_) => {
+            eprintln!("warning: ignoring --passes={}, it isn't a number", arg);
+            None
+        }
+    }
 }
 
-fn part1(input: &str) -> i32 {
-    input.lines()
-        .filter_map(|s| s.parse::<i32>().ok())
-        .sum()
+/// Prints both parts, and the report if asked for. The first repeat can
+/// overflow even when the total doesn't, so that's reported as part 2.
+fn print_answers(
+    changes: &[i64],
+    with_report: bool,
+    passes: Option<usize>,
+) -> Result<(), Overflow> {
+    println!("Part 1: {}", part1(changes)?);
+
+    match part2(changes) {
+        Ok(Some(frequency)) => println!("Part 2: {}", frequency),
+        Ok(None) => println!("Part 2: none"),
+        Err(e) => {
+            println!("Part 2: error: {}", e);
+            return Ok(());
+        }
+    }
+
+    // part 2 has already shown this doesn't overflow
+    let repeat = first_repeat(changes)?;
+    if let Some(repeat) = &repeat {
+        println!("        reached after {} passes", repeat.passes);
+    }
+
+    if with_report {
+        let passes = report_passes(repeat.as_ref(), passes);
+        println!();
+        print!("{}", analyze(changes, passes, repeat)?);
+    }
+
+    Ok(())
+}
+
+/// Writes the running frequency as CSV to stdout
+fn write_series(changes: &[i64], passes: Option<usize>) -> Result<(), Overflow> {
+    let repeat = first_repeat(changes)?;
+    let report = analyze(changes, report_passes(repeat.as_ref(), passes), repeat)?;
+
+    let stdout = io::stdout();
+    if let Err(e) = report.write_csv(&mut stdout.lock()) {
+        eprintln!("error: {}", e);
+        process::exit(1);
+    }
+
+    Ok(())
+}
+
+/// The passes asked for, or else enough to see the first repeat happen (just
+/// one if it never does), but never more than `MAX_PASSES`
+fn report_passes(first_repeat: Option<&Repeat>, requested: Option<usize>) -> usize {
+    requested
+        .unwrap_or_else(|| first_repeat.map_or(1, |repeat| repeat.passes))
+        .min(MAX_PASSES)
+}
+
+/// A line of the input that isn't a frequency change
+#[derive(Debug, PartialEq)]
+struct BadLine {
+    number: usize, // counting from 1
+    text: String,
+    reason: String,
+}
+
+impl fmt::Display for BadLine {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {:?}: {}", self.number, self.text, self.reason)
+    }
+}
+
+/// The frequency left the range of an `i64`, or the step it first repeats
+/// at doesn't fit in a `usize`
+#[derive(Debug, PartialEq)]
+struct Overflow;
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "the frequency or its step count doesn't fit")
+    }
 }
 
-fn part2(input: &str) -> i32 {
-    let mut last_seen = HashSet::new();
-    let mut total = 0;
+/// The first frequency that's reached twice, how many changes it takes to
+/// get there and how many passes over the list that is
+#[derive(Debug, PartialEq)]
+struct Repeat {
+    frequency: i64,
+    step: usize,
+    passes: usize,
+}
+
+/// What the frequency does over the first few passes over the changes
+#[derive(Debug, PartialEq)]
+struct Report {
+    passes: usize,
+    pass_len: usize,  // the number of changes
+    series: Vec<i64>, // the starting frequency, then one after every change
+    min: i64,
+    max: i64,
+    first_repeat: Option<Repeat>,
+    repeated: BTreeSet<i64>, // frequencies reached more than once in `passes`
+}
+
+/// Follows the frequency change by change for `passes` passes. The first
+/// repeat is the one `first_repeat` found for the same changes, so it's
+/// reported even when it takes more passes than that.
+fn analyze(
+    changes: &[i64],
+    passes: usize,
+    first_repeat: Option<Repeat>,
+) -> Result<Report, Overflow> {
+    let mut series = vec![0i64];
+    let mut seen: HashSet<i64> = vec![0].into_iter().collect();
+    let mut repeated = BTreeSet::new();
+
+    for &c in changes.iter().cycle().take(changes.len() * passes) {
+        let f = series[series.len() - 1].checked_add(c).ok_or(Overflow)?;
+        // insert fails for a frequency that has been seen before
+        if !seen.insert(f) {
+            repeated.insert(f);
+        }
+        series.push(f);
+    }
+
+    Ok(Report {
+        passes,
+        pass_len: changes.len(),
+        min: *series.iter().min().unwrap(),
+        max: *series.iter().max().unwrap(),
+        series,
+        first_repeat,
+        repeated,
+    })
+}
+
+impl Report {
+    /// The pass a step of the series happens in, counting from 1. The
+    /// starting frequency is in pass 0.
+    fn pass_of(&self, step: usize) -> usize {
+        step.div_ceil(self.pass_len.max(1))
+    }
+
+    /// One row per step of the series, flagging frequencies seen before
+    fn write_csv<W: Write>(&self, out: &mut W) -> io::Result<()> {
+        writeln!(out, "step,pass,frequency,seen_before")?;
+
+        let mut seen = HashSet::new();
+        for (step, &f) in self.series.iter().enumerate() {
+            let seen_before = !seen.insert(f);
+            writeln!(out, "{},{},{},{}", step, self.pass_of(step), f, seen_before)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Changes per pass: {}", self.pass_len)?;
+        writeln!(f, "Passes followed: {}", self.passes)?;
+        writeln!(f, "Lowest frequency: {}", self.min)?;
+        writeln!(f, "Highest frequency: {}", self.max)?;
+
+        match &self.first_repeat {
+            Some(repeat) => writeln!(
+                f,
+                "First repeat: {} at step {} (pass {})",
+                repeat.frequency, repeat.step, repeat.passes
+            )?,
+            None => writeln!(f, "First repeat: none")?,
+        }
+
+        let repeated: Vec<String> = self.repeated.iter().map(|r| r.to_string()).collect();
+        writeln!(
+            f,
+            "Frequencies repeated within {} passes: {}",
+            self.passes,
+            if repeated.is_empty() {
+                "none".to_string()
+            } else {
+                repeated.join(", ")
+            }
+        )
+    }
+}
+
+/// Splits the input into the changes that parse and the lines that don't
+fn parse(input: &str) -> (Vec<i64>, Vec<BadLine>) {
+    let mut changes = Vec::new();
+    let mut bad_lines = Vec::new();
+
+    for (i, line) in input.lines().enumerate() {
+        match line.trim().parse::<i64>() {
+            Ok(change) => changes.push(change),
+            Err(e) => bad_lines.push(BadLine {
+                number: i + 1,
+                text: line.to_string(),
+                reason: e.to_string(),
+            }),
+        }
+    }
+
+    (changes, bad_lines)
+}
+
+/// Every line has to be a change, otherwise all the bad lines are returned
+fn parse_strict(input: &str) -> Result<Vec<i64>, Vec<BadLine>> {
+    match parse(input) {
+        (changes, ref bad_lines) if bad_lines.is_empty() => Ok(changes),
+        (_, bad_lines) => Err(bad_lines),
+    }
+}
+
+/// Skips the lines that aren't changes, with a warning for each
+fn parse_lenient(input: &str) -> Vec<i64> {
+    let (changes, bad_lines) = parse(input);
+    for bad in bad_lines {
+        eprintln!("warning: skipping {}", bad);
+    }
 
-    input.lines()
-        .filter_map(|s| s.parse::<i32>().ok())
-        .cycle()
-        .find_map(|c| {         // find the first value that already exists
-            total += c;                     // in the set. replace returns an Option<T>
-            last_seen.replace(total)  // to indicate if the element was seen before.
-        }).unwrap()
+    changes
+}
+
+fn part1(changes: &[i64]) -> Result<i64, Overflow> {
+    changes
+        .iter()
+        .try_fold(0i64, |total, &c| total.checked_add(c))
+        .ok_or(Overflow)
+}
+
+/// The first frequency reached twice, or `None` if that never happens
+fn part2(changes: &[i64]) -> Result<Option<i64>, Overflow> {
+    Ok(first_repeat(changes)?.map(|repeat| repeat.frequency))
+}
+
+/// Finds the first repeated frequency without simulating every pass.
+///
+/// The frequencies seen during the first pass are the prefix sums `f[0] = 0,
+/// f[1], .., f[n - 1]`, and each later pass shifts all of them by the drift
+/// `d` (the sum of the changes). `f[j]` can only ever land on `f[i]` if the
+/// two are congruent modulo `d`, which it does after `(f[i] - f[j]) / d`
+/// more passes as long as that's positive. So only the nearest value in the
+/// direction of the drift within each residue class needs to be checked.
+fn first_repeat(changes: &[i64]) -> Result<Option<Repeat>, Overflow> {
+    let n = changes.len();
+    if n == 0 {
+        return Ok(None);
+    }
+
+    let mut prefix = vec![0i64];
+    for &c in &changes[..n - 1] {
+        let f = prefix[prefix.len() - 1].checked_add(c).ok_or(Overflow)?;
+        prefix.push(f);
+    }
+    let drift = part1(changes)?;
+
+    // a frequency repeated within the first pass beats anything later
+    let mut seen = HashSet::new();
+    for (step, &f) in prefix.iter().enumerate() {
+        if !seen.insert(f) {
+            return Ok(Some(Repeat {
+                frequency: f,
+                step,
+                passes: 1,
+            }));
+        }
+    }
+
+    // with no drift, the second pass starts at 0 again
+    if drift == 0 {
+        return Ok(Some(Repeat {
+            frequency: 0,
+            step: n,
+            passes: 1,
+        }));
+    }
+
+    // differences between two i64s can overflow, so work in i128 from here
+    let drift = i128::from(drift);
+
+    let mut classes: HashMap<i128, Vec<(i64, usize)>> = HashMap::new();
+    for (step, &f) in prefix.iter().enumerate() {
+        classes
+            .entry(i128::from(f).rem_euclid(drift.abs()))
+            .or_default()
+            .push((f, step));
+    }
+
+    // each candidate is (step the repeat happens at, frequency)
+    let mut candidates = Vec::new();
+    for class in classes.values_mut() {
+        class.sort();
+        if drift < 0 {
+            class.reverse();
+        }
+
+        for pair in class.windows(2) {
+            let ((from, step), (to, _)) = (pair[0], pair[1]);
+            let passes = (i128::from(to) - i128::from(from)) / drift;
+            candidates.push((passes as u128 * n as u128 + step as u128, to));
+        }
+    }
+
+    let (at, frequency) = match candidates.into_iter().min() {
+        Some(candidate) => candidate,
+        None => return Ok(None),
+    };
+
+    // every frequency on the way there has to fit as well. The furthest one
+    // along the drift for each starting point is from the last pass it's in.
+    for (step, &f) in prefix.iter().enumerate() {
+        let last_pass = (at - step as u128) / n as u128;
+        let furthest = i128::from(f) + last_pass as i128 * drift;
+        if furthest < i128::from(i64::MIN) || furthest > i128::from(i64::MAX) {
+            return Err(Overflow);
+        }
+    }
+
+    // the step can be too large for a usize on a 32 bit target
+    let step = usize::try_from(at).map_err(|_| Overflow)?;
+    let passes = usize::try_from(at.div_ceil(n as u128)).map_err(|_| Overflow)?;
+
+    Ok(Some(Repeat {
+        frequency,
+        step,
+        passes,
+    }))
 }
 
 #[cfg(test)]
@@ -32,8 +391,202 @@ mod tests {
 
     #[test]
     fn day01() {
-        assert_eq!(part1("-4\n+5\n+10"), 11);
-        assert_eq!(part2("-6\n+3\n+8\n+5\n-6"), 5);
-        assert_eq!(part2("+3\n+3\n+4\n-2\n-4"), 10);
+        assert_eq!(part1(&parse_strict("-4\n+5\n+10").unwrap()), Ok(11));
+        assert_eq!(
+            part2(&parse_strict("-6\n+3\n+8\n+5\n-6").unwrap()),
+            Ok(Some(5))
+        );
+        assert_eq!(
+            part2(&parse_strict("+3\n+3\n+4\n-2\n-4").unwrap()),
+            Ok(Some(10))
+        );
+        assert_eq!(part2(&[1, 1]), Ok(None));
+    }
+
+    #[test]
+    fn day01_first_repeat() {
+        assert_eq!(
+            first_repeat(&[1, -1]),
+            Ok(Some(Repeat {
+                frequency: 0,
+                step: 2,
+                passes: 1
+            }))
+        );
+        assert_eq!(
+            first_repeat(&[7, 7, -2, -7, -4]),
+            Ok(Some(Repeat {
+                frequency: 14,
+                step: 13,
+                passes: 3
+            }))
+        );
+        assert_eq!(first_repeat(&[1, 1]), Ok(None));
+        assert_eq!(first_repeat(&[]), Ok(None));
+    }
+
+    #[test]
+    fn day01_parse_strict() {
+        assert_eq!(parse_strict("+1\n-2\n+3"), Ok(vec![1, -2, 3]));
+
+        let bad_lines = parse_strict("+1\n+1O\n\n-4").unwrap_err();
+        let numbers: Vec<usize> = bad_lines.iter().map(|bad| bad.number).collect();
+        assert_eq!(numbers, vec![2, 3]);
+        assert_eq!(bad_lines[0].text, "+1O");
+        assert_eq!(
+            bad_lines[0].to_string(),
+            "line 2: \"+1O\": invalid digit found in string"
+        );
+
+        assert!(parse_strict("+99999999999999999999").is_err());
+    }
+
+    #[test]
+    fn day01_parse_lenient() {
+        assert_eq!(parse_lenient("+1\n+1O\n\n-4"), vec![1, -4]);
+    }
+
+    #[test]
+    fn day01_large_values() {
+        let big = i64::MAX / 2;
+        assert_eq!(part1(&[big, big, -big]), Ok(big));
+        assert_eq!(part1(&[big, big, big]), Err(Overflow));
+        assert_eq!(first_repeat(&[big, big, big, -big]), Err(Overflow));
+
+        // `big` comes round again after `big` passes without overflowing
+        assert_eq!(
+            first_repeat(&[big, 1 - big]),
+            Ok(Some(Repeat {
+                frequency: big,
+                step: 2 * big as usize,
+                passes: big as usize
+            }))
+        );
+
+        // these two are congruent modulo the drift, but the distance between
+        // them doesn't fit in an i64 and the frequency overflows long before
+        // one reaches the other
+        let changes = [i64::MIN + 1, i64::MAX - 3, i64::MAX - 16, 24 - i64::MAX];
+        assert_eq!(first_repeat(&changes), Err(Overflow));
+
+        // 1 - i64::MAX climbs all the way to 0, just under 2^64 steps in
+        let changes = [1 - i64::MAX, i64::MAX];
+        let step = usize::try_from(2 * i64::MAX as u64 - 1);
+        let passes = usize::try_from(i64::MAX);
+        match (step, passes) {
+            (Ok(step), Ok(passes)) => assert_eq!(
+                first_repeat(&changes),
+                Ok(Some(Repeat {
+                    frequency: 0,
+                    step,
+                    passes
+                }))
+            ),
+            _ => assert_eq!(first_repeat(&changes), Err(Overflow)),
+        }
+    }
+
+    #[test]
+    fn day01_report() {
+        let changes = [7, 7, -2, -7, -4];
+        let report = analyze(&changes, 3, first_repeat(&changes).unwrap()).unwrap();
+
+        assert_eq!(report.series.len(), 16);
+        assert_eq!(&report.series[..6], &[0, 7, 14, 12, 5, 1]);
+        assert_eq!((report.min, report.max), (0, 16));
+        assert_eq!(report.first_repeat.as_ref().unwrap().step, 13);
+        assert_eq!(report.repeated, vec![7, 14].into_iter().collect());
+
+        // the first repeat is still found when it's past the passes followed
+        let report = analyze(&changes, 1, first_repeat(&changes).unwrap()).unwrap();
+        assert!(report.repeated.is_empty());
+        assert_eq!(report.first_repeat.as_ref().unwrap().passes, 3);
+        assert!(report
+            .to_string()
+            .ends_with("Frequencies repeated within 1 passes: none\n"));
+
+        let report = analyze(&changes, 4, None).unwrap();
+        assert!(report
+            .to_string()
+            .ends_with("Frequencies repeated within 4 passes: 7, 8, 14, 15\n"));
+    }
+
+    #[test]
+    fn day01_report_passes() {
+        let repeat = Repeat {
+            frequency: 14,
+            step: 13,
+            passes: 3,
+        };
+        assert_eq!(report_passes(Some(&repeat), None), 3);
+        assert_eq!(report_passes(Some(&repeat), Some(5)), 5);
+        assert_eq!(report_passes(None, None), 1);
+
+        // the repeat in `[big, 1 - big]` takes `big` passes to happen
+        let far = Repeat {
+            frequency: i64::MAX / 2,
+            step: usize::MAX,
+            passes: usize::MAX / 2,
+        };
+        assert_eq!(report_passes(Some(&far), None), MAX_PASSES);
+        assert_eq!(report_passes(None, Some(usize::MAX)), MAX_PASSES);
+    }
+
+    #[test]
+    fn day01_report_csv() {
+        let mut out = Vec::new();
+        analyze(&[1, -1], 1, None)
+            .unwrap()
+            .write_csv(&mut out)
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "step,pass,frequency,seen_before
+0,0,0,false
+1,1,1,false
+2,1,0,true
+"
+        );
+    }
+
+    #[test]
+    fn day01_first_repeat_matches_simulation() {
+        // the old brute force approach, capped so that it always terminates
+        fn simulate(changes: &[i64]) -> Option<Repeat> {
+            let mut seen: HashSet<i64> = vec![0].into_iter().collect();
+            let mut total = 0;
+            for step in 0..changes.len() * 100 {
+                total += changes[step % changes.len()];
+                if !seen.insert(total) {
+                    return Some(Repeat {
+                        frequency: total,
+                        step: step + 1,
+                        passes: step / changes.len() + 1,
+                    });
+                }
+            }
+            None
+        }
+
+        let inputs: Vec<Vec<i64>> = vec![
+            vec![3, 3, 4, -2, -4],
+            vec![-6, 3, 8, 5, -6],
+            vec![5, -3, 4, -7, 2],
+            vec![-5, 3, -4, 7, -2],
+            vec![10, -9, 10, -9],
+            vec![2, -1, 3, -3, 4, -4],
+            vec![0],
+            vec![4],
+        ];
+
+        for changes in inputs {
+            assert_eq!(
+                first_repeat(&changes),
+                Ok(simulate(&changes)),
+                "{:?}",
+                changes
+            );
+        }
     }
 }
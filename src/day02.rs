@@ -1,8 +1,7 @@
-use std::collections::HashMap;
-
-extern crate itertools;
-
-use itertools::Itertools;
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::fmt;
+use std::process;
 
 const INPUT: &str = include_str!("../input/day02.txt");
 
@@ -10,50 +9,285 @@ fn main() {
     let lines: Vec<_> = INPUT.lines().collect();
     println!("part1: {}", part1(&lines));
     println!("part2: {:?}", part2(&lines));
+
+    // `--clusters=K` lists groups of IDs that are all at most K apart
+    let k = env::args().find_map(|arg| {
+        arg.strip_prefix("--clusters=")
+            .and_then(|k| k.parse::<usize>().ok())
+    });
+
+    if let Some(k) = k {
+        match clusters(&lines, k) {
+            Ok(clusters) => {
+                for cluster in clusters {
+                    println!("{} IDs share {}", cluster.members.len(), cluster.common);
+                }
+            }
+            Err(e) => {
+                eprintln!("error: {}", e);
+                process::exit(1);
+            }
+        }
+    }
 }
 
-fn part1(words: &Vec<&str>) -> usize {
-    let counters: Vec<HashMap<_, _>> = words.iter().map(|x| to_counter(x)).collect();
-    let twos = counters.iter().filter(|s| has_exactly(s, 2)).count();
-    let threes = counters.iter().filter(|s| has_exactly(s, 3)).count();
+fn part1(words: &[&str]) -> usize {
+    checksum(words, &[2, 3])
+}
 
-    twos * threes
+/// For each multiplicity, the number of IDs with some letter that appears
+/// exactly that many times, all multiplied together
+fn checksum(words: &[&str], multiplicities: &[u32]) -> usize {
+    let breakdown = breakdown(words, multiplicities);
+
+    multiplicities
+        .iter()
+        .map(|m| {
+            breakdown
+                .iter()
+                .filter(|(_, found)| found.contains(m))
+                .count()
+        })
+        .product()
 }
 
-fn part2(words: &Vec<&str>) -> Option<String> {
-    words.iter()
-        .cartesian_product(words.clone())
-        .filter(|(a, b)| *a != b)
-        .find(|(a, b)| differing_chars(a, b))
-        .map(|(x, y)| matching_chars(x, y))
+/// Which of the multiplicities each ID contributes to the checksum
+fn breakdown<'a>(words: &[&'a str], multiplicities: &[u32]) -> Vec<(&'a str, Vec<u32>)> {
+    words
+        .iter()
+        .map(|word| {
+            let counts = to_counter(word);
+            let found = multiplicities
+                .iter()
+                .cloned()
+                .filter(|&m| has_exactly(&counts, m))
+                .collect();
+            (*word, found)
+        })
+        .collect()
 }
 
-fn differing_chars(s1: &str, s2: &str) -> bool {
-    s1.chars()
-      .zip(s2.chars())
-      .filter(|(a, b)| a != b)
-      .count() <= 1
+fn part2(words: &[&str]) -> Option<String> {
+    near_duplicates(words)
+        .first()
+        .map(|&(i, j)| matching_chars(words[i], words[j]))
+}
+
+/// Every pair of words that differ in exactly one position, as indices into
+/// `words` with the smaller one first, sorted.
+///
+/// Instead of comparing every pair, each word is filed under every way of
+/// masking out one of its characters, keyed by the text before and after the
+/// mask. Two words end up under the same key only if they agree everywhere
+/// except at the masked position, so the work grows with the number of
+/// words times their length rather than with the number of pairs. Copies of
+/// a word are only filed once, since they'd otherwise all share every key
+/// without ever making a pair.
+fn near_duplicates(words: &[&str]) -> Vec<(usize, usize)> {
+    let mut copies: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (i, &word) in words.iter().enumerate() {
+        copies.entry(word).or_default().push(i);
+    }
+
+    let mut masked: HashMap<(usize, &str, &str), Vec<&str>> = HashMap::new();
+    for &word in copies.keys() {
+        for (p, (start, c)) in word.char_indices().enumerate() {
+            let key = (p, &word[..start], &word[start + c.len_utf8()..]);
+            masked.entry(key).or_default().push(word);
+        }
+    }
+
+    // distinct words under the same key differ in just the masked position,
+    // so every copy of one pairs up with every copy of the other
+    let mut pairs = Vec::new();
+    for group in masked.values() {
+        for (n, first) in group.iter().enumerate() {
+            for second in &group[n + 1..] {
+                for &i in &copies[first] {
+                    for &j in &copies[second] {
+                        pairs.push((i.min(j), i.max(j)));
+                    }
+                }
+            }
+        }
+    }
+
+    pairs.sort();
+    pairs
+}
+
+/// Two IDs whose Hamming distance was asked for aren't the same length
+#[derive(Debug, PartialEq)]
+struct LengthMismatch {
+    left: usize,
+    right: usize,
+}
+
+impl fmt::Display for LengthMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "can't compare IDs of {} and {} characters",
+            self.left, self.right
+        )
+    }
+}
+
+/// Number of positions at which two IDs of the same length differ
+fn hamming(s1: &str, s2: &str) -> Result<usize, LengthMismatch> {
+    let (left, right) = (s1.chars().count(), s2.chars().count());
+    if left != right {
+        return Err(LengthMismatch { left, right });
+    }
+
+    Ok(s1.chars().zip(s2.chars()).filter(|(a, b)| a != b).count())
+}
+
+/// Every pair of IDs at most `k` positions apart, as sorted index pairs with
+/// the smaller index first. All the IDs have to be the same length.
+///
+/// Each ID is cut into `k + 1` blocks. Two IDs that differ in at most `k`
+/// positions must agree on at least one whole block, so only IDs that share
+/// a block are compared.
+fn within_distance(words: &[&str], k: usize) -> Result<Vec<(usize, usize)>, LengthMismatch> {
+    let chars: Vec<Vec<char>> = words.iter().map(|w| w.chars().collect()).collect();
+    let len = chars.first().map_or(0, |c| c.len());
+    if let Some(other) = chars.iter().find(|c| c.len() != len) {
+        return Err(LengthMismatch {
+            left: len,
+            right: other.len(),
+        });
+    }
+
+    // too short for any two IDs to be more than k apart
+    if len <= k {
+        let n = words.len();
+        return Ok((0..n)
+            .flat_map(|i| (i + 1..n).map(move |j| (i, j)))
+            .collect());
+    }
+
+    let blocks = k + 1;
+    let mut by_block: HashMap<(usize, &[char]), Vec<usize>> = HashMap::new();
+    for (i, c) in chars.iter().enumerate() {
+        for b in 0..blocks {
+            let block = &c[b * len / blocks..(b + 1) * len / blocks];
+            by_block.entry((b, block)).or_default().push(i);
+        }
+    }
+
+    let mut pairs = HashSet::new();
+    for group in by_block.values() {
+        for (n, &i) in group.iter().enumerate() {
+            for &j in &group[n + 1..] {
+                if !pairs.contains(&(i, j)) && hamming(words[i], words[j])? <= k {
+                    pairs.insert((i, j));
+                }
+            }
+        }
+    }
+
+    let mut pairs: Vec<_> = pairs.into_iter().collect();
+    pairs.sort();
+    Ok(pairs)
+}
+
+/// IDs that are all at most `k` apart from each other
+#[derive(Debug, PartialEq)]
+struct Cluster {
+    members: Vec<usize>, // indices into the IDs, sorted
+    common: String,      // the characters every member has in the same place
+}
+
+/// Groups the IDs into clusters of two or more, where every pair of members
+/// is at most `k` apart. The grouping is greedy: going through the IDs in
+/// order, each one that isn't in a cluster yet takes in every later free ID
+/// that is within `k` of all the members so far. An ID is in at most one
+/// cluster, so one that is only close to already clustered IDs is left out.
+fn clusters(words: &[&str], k: usize) -> Result<Vec<Cluster>, LengthMismatch> {
+    let pairs = within_distance(words, k)?;
+
+    // the pairs are sorted, so each ID's later neighbors are too
+    let mut later: Vec<Vec<usize>> = vec![Vec::new(); words.len()];
+    for &(i, j) in &pairs {
+        later[i].push(j);
+    }
+    let close: HashSet<(usize, usize)> = pairs.into_iter().collect();
+
+    let mut taken = vec![false; words.len()];
+    let mut clusters = Vec::new();
+
+    for i in 0..words.len() {
+        if taken[i] {
+            continue;
+        }
+
+        let mut members = vec![i];
+        for &j in &later[i] {
+            if !taken[j] && members.iter().all(|&m| close.contains(&(m, j))) {
+                members.push(j);
+            }
+        }
+
+        if members.len() > 1 {
+            for &m in &members {
+                taken[m] = true;
+            }
+            let ids: Vec<&str> = members.iter().map(|&m| words[m]).collect();
+            clusters.push(Cluster {
+                common: common_chars(&ids),
+                members,
+            });
+        }
+    }
+
+    Ok(clusters)
+}
+
+/// The characters that all the IDs have at the same position, in order
+fn common_chars(words: &[&str]) -> String {
+    let mut columns: Vec<std::str::Chars> = words.iter().map(|w| w.chars()).collect();
+    let mut common = String::new();
+
+    loop {
+        let column: Option<Vec<char>> = columns.iter_mut().map(|c| c.next()).collect();
+        match column {
+            Some(ref column) if column.is_empty() => break,
+            Some(column) => {
+                if column.iter().all(|&c| c == column[0]) {
+                    common.push(column[0]);
+                }
+            }
+            None => break,
+        }
+    }
+
+    common
 }
 
 fn matching_chars(s1: &str, s2: &str) -> String {
     s1.chars()
-      .zip(s2.chars())
-      .filter(|(a, b)| a == b)
-      .map(|(a, _)| a)
-      .collect()
+        .zip(s2.chars())
+        .filter(|(a, b)| a == b)
+        .map(|(a, _)| a)
+        .collect()
 }
 
-fn to_counter(s: &str) -> HashMap<String, i32> {
-    let mut counts: HashMap<String, i32> = HashMap::new();
-    for a in s.chars() {
-        let c = counts.entry(a.to_string()).or_insert(0);
-        *c += 1;
+// how many times each byte appears in an ID. IDs are ASCII, so that's the
+// same as counting letters.
+type Counter = [u32; 256];
+
+fn to_counter(s: &str) -> Counter {
+    let mut counts = [0; 256];
+    for b in s.bytes() {
+        counts[b as usize] += 1;
     }
     counts
 }
 
-fn has_exactly(counts: &HashMap<String, i32>, n: i32) -> bool {
-    counts.values().any(|c| *c == n)
+fn has_exactly(counts: &Counter, n: u32) -> bool {
+    // every letter that doesn't appear at all would match 0
+    n > 0 && counts.contains(&n)
 }
 
 #[cfg(test)]
@@ -62,22 +296,177 @@ mod tests {
 
     #[test]
     fn day02_part1() {
-        assert_eq!(has_exactly(&to_counter("bababc"), 2), true);
-        assert_eq!(has_exactly(&to_counter("bababc"), 3), true);
-        assert_eq!(has_exactly(&to_counter("bababc"), 4), false);
-        assert_eq!(has_exactly(&to_counter("ababab"), 3), true);
+        assert!(has_exactly(&to_counter("bababc"), 2));
+        assert!(has_exactly(&to_counter("bababc"), 3));
+        assert!(!has_exactly(&to_counter("bababc"), 4));
+        assert!(has_exactly(&to_counter("ababab"), 3));
 
-        let inputs = vec!["abcdef", "bababc", "abbcde", "abcccd", "aabcdd", "abcdee", "ababab"];
+        let inputs = vec![
+            "abcdef", "bababc", "abbcde", "abcccd", "aabcdd", "abcdee", "ababab",
+        ];
         assert_eq!(part1(&inputs), 12);
     }
 
+    #[test]
+    fn day02_checksum() {
+        let inputs = vec![
+            "abcdef", "bababc", "abbcde", "abcccd", "aabcdd", "abcdee", "ababab",
+        ];
+        assert_eq!(checksum(&inputs, &[2, 3]), 12);
+        assert_eq!(checksum(&inputs, &[2]), 4);
+        assert_eq!(checksum(&inputs, &[1, 3]), 6 * 3);
+        assert_eq!(checksum(&inputs, &[4]), 0);
+        assert_eq!(checksum(&inputs, &[0, 2]), 0);
+
+        let breakdown = breakdown(&inputs[..3], &[2, 3]);
+        assert_eq!(
+            breakdown,
+            vec![
+                ("abcdef", vec![]),
+                ("bababc", vec![2, 3]),
+                ("abbcde", vec![2])
+            ]
+        );
+    }
+
     #[test]
     fn day02_part2() {
-        let inputs = vec!["abcde", "fghij", "klmno", "pqrst", "fguij", "axcye", "wvxyz"];
-        assert_eq!(differing_chars("abcde", "fghij"), false);
-        assert_eq!(differing_chars("fguij", "fghij"), true);
-        assert_eq!(differing_chars("axcye", "fghij"), false);
+        let inputs = vec![
+            "abcde", "fghij", "klmno", "pqrst", "fguij", "axcye", "wvxyz",
+        ];
         assert_eq!(matching_chars("fguij", "fghij"), "fgij".to_string());
         assert_eq!(part2(&inputs), Some("fgij".to_string()));
     }
+
+    #[test]
+    fn day02_near_duplicates() {
+        let inputs = vec![
+            "abcde", "fghij", "klmno", "pqrst", "fguij", "axcye", "wvxyz",
+        ];
+        assert_eq!(near_duplicates(&inputs), vec![(1, 4)]);
+
+        // every pair is found, repeated words don't count and words of
+        // different lengths never match
+        let inputs = vec!["abc", "abd", "xbc", "abc", "abcd", "abd", "ab"];
+        assert_eq!(
+            near_duplicates(&inputs),
+            vec![(0, 1), (0, 2), (0, 5), (1, 3), (2, 3), (3, 5)]
+        );
+
+        assert!(near_duplicates(&[]).is_empty());
+        assert!(near_duplicates(&["", ""]).is_empty());
+    }
+
+    #[test]
+    fn day02_hamming() {
+        assert_eq!(hamming("abcde", "axcye"), Ok(2));
+        assert_eq!(hamming("fguij", "fghij"), Ok(1));
+        assert_eq!(hamming("", ""), Ok(0));
+        assert_eq!(
+            hamming("abc", "abcd"),
+            Err(LengthMismatch { left: 3, right: 4 })
+        );
+    }
+
+    #[test]
+    fn day02_within_distance() {
+        let inputs = vec![
+            "abcde", "fghij", "klmno", "pqrst", "fguij", "axcye", "wvxyz",
+        ];
+        assert_eq!(within_distance(&inputs, 1), Ok(vec![(1, 4)]));
+        assert_eq!(within_distance(&inputs, 2), Ok(vec![(0, 5), (1, 4)]));
+        assert_eq!(within_distance(&inputs, 0), Ok(vec![]));
+        assert_eq!(within_distance(&inputs, 5).unwrap().len(), 21);
+
+        assert_eq!(
+            within_distance(&["abc", "abd", "ab"], 1),
+            Err(LengthMismatch { left: 3, right: 2 })
+        );
+    }
+
+    #[test]
+    fn day02_within_distance_matches_brute_force() {
+        let words: Vec<String> = (0..300u32)
+            .map(|n| format!("{:06}", n.wrapping_mul(2_654_435_761) % 1_000_000))
+            .collect();
+        let words: Vec<&str> = words.iter().map(|w| w.as_str()).collect();
+
+        for k in 0..4 {
+            let mut expected = Vec::new();
+            for i in 0..words.len() {
+                for j in i + 1..words.len() {
+                    if hamming(words[i], words[j]).unwrap() <= k {
+                        expected.push((i, j));
+                    }
+                }
+            }
+            assert_eq!(within_distance(&words, k), Ok(expected), "k = {}", k);
+        }
+    }
+
+    #[test]
+    fn day02_clusters() {
+        // abcdx is 1 from both abcde and zbcdx, but those two are 2 apart
+        let inputs = vec!["abcde", "fghij", "abcdx", "fguij", "zbcdx", "qqqqq"];
+        assert_eq!(
+            clusters(&inputs, 1),
+            Ok(vec![
+                Cluster {
+                    members: vec![0, 2],
+                    common: "abcd".to_string()
+                },
+                Cluster {
+                    members: vec![1, 3],
+                    common: "fgij".to_string()
+                },
+            ])
+        );
+
+        assert_eq!(
+            clusters(&inputs, 2),
+            Ok(vec![
+                Cluster {
+                    members: vec![0, 2, 4],
+                    common: "bcd".to_string()
+                },
+                Cluster {
+                    members: vec![1, 3],
+                    common: "fgij".to_string()
+                },
+            ])
+        );
+
+        assert_eq!(clusters(&inputs, 0), Ok(vec![]));
+        assert!(clusters(&["ab", "abc"], 1).is_err());
+        assert_eq!(
+            common_chars(&["fghij", "fguij"]),
+            matching_chars("fghij", "fguij")
+        );
+    }
+
+    #[test]
+    fn day02_near_duplicates_many_words() {
+        // 20,000 words that are at least two positions apart, plus one that's
+        // a single position away from the last of them
+        let mut words: Vec<String> = (0..20_000u32)
+            .map(|n| format!("{:05}{:05}", n, n))
+            .collect();
+        words.push("199991999x".to_string());
+
+        let words: Vec<&str> = words.iter().map(|w| w.as_str()).collect();
+        assert_eq!(near_duplicates(&words), vec![(19_999, 20_000)]);
+    }
+
+    #[test]
+    fn day02_near_duplicates_repeated_words() {
+        // copies of the same word never pair up with each other
+        let mut words = vec!["abcdefghij"; 30_000];
+        assert!(near_duplicates(&words).is_empty());
+
+        words.push("abcdefghix");
+        let pairs = near_duplicates(&words);
+        assert_eq!(pairs.len(), 30_000);
+        assert_eq!(pairs[0], (0, 30_000));
+        assert_eq!(pairs[29_999], (29_999, 30_000));
+    }
 }
@@ -1,5 +1,8 @@
 extern crate regex;
 use regex::Regex;
+use serde_derive::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::env;
 use std::error::Error;
 use std::result;
 
@@ -8,11 +11,13 @@ extern crate lazy_static;
 
 mod matrix;
 
+use matrix::Pos;
+
 const INPUT: &str = include_str!("../input/day03.txt");
 
-type Result<T> = result::Result<T, Box<Error>>;
+type Result<T> = result::Result<T, Box<dyn Error>>;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
 struct Claim {
     id: u32,
     x: u32,
@@ -29,7 +34,11 @@ impl Claim {
 
         let captures = match RE.captures(s) {
             Some(captures) => captures,
-            None => return Err(Box::<Error>::from(format!("invalid format for claim"))),
+            None => {
+                return Err(Box::<dyn Error>::from(
+                    "invalid format for claim".to_string(),
+                ))
+            }
         };
 
         Ok(Claim {
@@ -40,24 +49,153 @@ impl Claim {
             height: captures[5].parse()?,
         })
     }
+
+    /// The fabric the claim covers. The edges are widened to u64 so that
+    /// `x + width` can't overflow.
+    fn rect(&self) -> Rect {
+        Rect::new(self.x, self.y, self.width, self.height)
+    }
+}
+
+/// A rectangle of fabric from `left, top` up to, but not including,
+/// `right, bottom`. Two rectangles that only share an edge don't overlap.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+struct Rect {
+    left: u64,
+    top: u64,
+    right: u64,
+    bottom: u64,
+}
+
+// not all of these are needed to solve the puzzle
+#[allow(dead_code)]
+impl Rect {
+    fn new(x: u32, y: u32, width: u32, height: u32) -> Rect {
+        let (x, y) = (u64::from(x), u64::from(y));
+        Rect {
+            left: x,
+            top: y,
+            right: x + u64::from(width),
+            bottom: y + u64::from(height),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.left >= self.right || self.top >= self.bottom
+    }
+
+    /// Square inches covered, saturating at `u64::MAX`
+    fn area(&self) -> u64 {
+        let width = self.right.saturating_sub(self.left);
+        let height = self.bottom.saturating_sub(self.top);
+        width.saturating_mul(height)
+    }
+
+    /// The fabric both rectangles cover, if there's any
+    fn intersection(&self, other: &Rect) -> Option<Rect> {
+        let shared = Rect {
+            left: self.left.max(other.left),
+            top: self.top.max(other.top),
+            right: self.right.min(other.right),
+            bottom: self.bottom.min(other.bottom),
+        };
+
+        if shared.is_empty() {
+            None
+        } else {
+            Some(shared)
+        }
+    }
+
+    fn overlaps(&self, other: &Rect) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    fn contains_point(&self, x: u64, y: u64) -> bool {
+        self.left <= x && x < self.right && self.top <= y && y < self.bottom
+    }
 }
 
 fn main() {
-    let claims: Vec<Claim> = INPUT.lines()
+    let claims: Vec<Claim> = INPUT
+        .lines()
         .map(Claim::from)
-        .filter_map(Result::ok).collect();
+        .filter_map(Result::ok)
+        .collect();
 
     println!("Part 1: {}", part1(&claims));
     println!("Part 2: {:?}", part2(&claims));
+
+    if env::args().any(|arg| arg == "--disputes") {
+        print_disputes(&claims);
+    }
+
+    // `--withdraw=ID` takes that claim off the fabric and shows what's left.
+    // It can be given more than once, and each claim stays withdrawn, so
+    // every line shows the fabric without all the claims withdrawn so far.
+    let withdrawn: Vec<u32> = env::args()
+        .filter_map(|arg| {
+            arg.strip_prefix("--withdraw=")
+                .and_then(|id| id.parse().ok())
+        })
+        .collect();
+
+    if !withdrawn.is_empty() {
+        let mut fabric = Fabric::new();
+        for claim in &claims {
+            fabric.add(claim);
+        }
+
+        for id in withdrawn {
+            if !fabric.withdraw(id) {
+                println!("There's no claim #{}", id);
+                continue;
+            }
+            println!(
+                "Without #{}: {} sq in contested, uncontested claims {:?}",
+                id,
+                fabric.contested(),
+                fabric.uncontested()
+            );
+        }
+    }
+}
+
+/// Lists every group of overlapping claims and what each claim overlaps
+fn print_disputes(claims: &[Claim]) {
+    let index = ClaimIndex::new(claims);
+    let rects: Vec<Rect> = claims.iter().map(Claim::rect).collect();
+    println!(
+        "{} sq in claimed, {} sq in disputed",
+        union_area(&rects),
+        overlap_area(claims)
+    );
+
+    for component in index.components().iter().filter(|c| c.len() > 1) {
+        println!("{} claims:", component.len());
+        for &id in component {
+            let overlaps: Vec<String> = index
+                .overlaps(id)
+                .unwrap()
+                .iter()
+                .map(|o| format!("#{} ({} sq in)", o.id, o.area))
+                .collect();
+            println!("  #{} overlaps {}", id, overlaps.join(", "));
+        }
+    }
 }
 
-fn build_grid(claims: &Vec<Claim>) -> matrix::Matrix<u32> {
-    let mut grid = matrix::Matrix::new(1000, 1000, 0);
+// fabrics with up to this many square inches in their bounding box are
+// small enough to count overlaps cell by cell
+const DENSE_LIMIT: u64 = 1 << 22;
 
-    for claim in claims {
-        for i in claim.x..(claim.x + claim.width) {
-            for j in claim.y..(claim.y + claim.height) {
-                grid[(i as usize, j as usize)] += 1;
+fn build_grid(claims: &[Claim]) -> matrix::OffsetMatrix<u32> {
+    let mut grid = matrix::OffsetMatrix::new(0);
+
+    for rect in claims.iter().map(Claim::rect) {
+        for x in rect.left..rect.right {
+            for y in rect.top..rect.bottom {
+                grid[Pos::new(x as isize, y as isize)] += 1;
             }
         }
     }
@@ -65,39 +203,379 @@ fn build_grid(claims: &Vec<Claim>) -> matrix::Matrix<u32> {
     grid
 }
 
-fn part1(claims: &Vec<Claim>) -> u32 {
-    let grid = build_grid(claims);
+fn part1(claims: &[Claim]) -> u64 {
+    if fabric_area(claims) <= DENSE_LIMIT {
+        build_grid(claims).to_matrix().count_where(|v| *v > 1) as u64
+    } else {
+        overlap_area(claims)
+    }
+}
+
+/// The area of the smallest rectangle that covers every claim
+fn fabric_area(claims: &[Claim]) -> u64 {
+    let rects: Vec<Rect> = claims.iter().map(Claim::rect).collect();
+    let span = |start: fn(&Rect) -> u64, end: fn(&Rect) -> u64| {
+        let min = rects.iter().map(start).min().unwrap_or(0);
+        let max = rects.iter().map(end).max().unwrap_or(0);
+        max - min
+    };
 
-    grid.rows()
-        .map(|row| row.into_iter().filter(|v| **v > 1).count())
-        .fold(0, |acc, val| acc + (val as u32))
+    span(|r| r.left, |r| r.right).saturating_mul(span(|r| r.top, |r| r.bottom))
 }
 
-fn part2(claims: &Vec<Claim>) -> Option<u32> {
-    let grid = build_grid(claims);
+/// Square inches covered by two or more claims
+fn overlap_area(claims: &[Claim]) -> u64 {
+    let rects: Vec<Rect> = claims.iter().map(Claim::rect).collect();
+    covered_area(&rects).1
+}
+
+/// Square inches covered by at least one of the rectangles
+fn union_area(rects: &[Rect]) -> u64 {
+    covered_area(rects).0
+}
+
+/// The area covered at least once and at least twice, found by sweeping a
+/// vertical line across the rectangles. The line stops at every left and
+/// right edge, and a segment tree over the distinct top and bottom edges
+/// keeps track of how much of the line is covered. That takes O(n log n) for
+/// n rectangles no matter how far apart they are.
+fn covered_area(rects: &[Rect]) -> (u64, u64) {
+    let mut ys: Vec<u64> = rects.iter().flat_map(|r| vec![r.top, r.bottom]).collect();
+    ys.sort();
+    ys.dedup();
+
+    // (x, +1 for a left edge or -1 for a right one, top, bottom)
+    let mut events: Vec<(u64, i32, u64, u64)> = Vec::new();
+    for r in rects.iter().filter(|r| !r.is_empty()) {
+        events.push((r.left, 1, r.top, r.bottom));
+        events.push((r.right, -1, r.top, r.bottom));
+    }
+    events.sort();
+
+    let mut tree = CoverTree::new(ys);
+    let (mut once, mut twice) = (0u64, 0u64);
+    let mut last_x = 0;
+
+    for (x, delta, top, bottom) in events {
+        let width = x - last_x;
+        once = once.saturating_add(tree.covered_once().saturating_mul(width));
+        twice = twice.saturating_add(tree.covered_twice().saturating_mul(width));
+
+        tree.add(top, bottom, delta);
+        last_x = x;
+    }
+
+    (once, twice)
+}
+
+/// A segment tree over the gaps between sorted coordinates that tracks how
+/// much of their total length is covered at least once and at least twice
+struct CoverTree {
+    coords: Vec<u64>,
+    count: Vec<i32>, // ranges that cover the whole of a node
+    once: Vec<u64>,  // length of a node covered at least once
+    twice: Vec<u64>, // length of a node covered at least twice
+}
+
+impl CoverTree {
+    fn new(coords: Vec<u64>) -> CoverTree {
+        let nodes = 4 * coords.len().max(1);
+        CoverTree {
+            coords,
+            count: vec![0; nodes],
+            once: vec![0; nodes],
+            twice: vec![0; nodes],
+        }
+    }
 
-    for claim in claims {
-        let mut nonoverlapping = true;
-        for i in claim.x..(claim.x + claim.width) {
-            for j in claim.y..(claim.y + claim.height) {
-                if grid[(i as usize,j as usize)] > 1 {
-                    nonoverlapping = false;
-                    continue;
+    fn covered_once(&self) -> u64 {
+        self.once[1]
+    }
+
+    fn covered_twice(&self) -> u64 {
+        self.twice[1]
+    }
+
+    /// Adds `delta` to the cover of `start..end`, both of which must be
+    /// among the coordinates
+    fn add(&mut self, start: u64, end: u64, delta: i32) {
+        let from = self.coords.binary_search(&start).unwrap();
+        let to = self.coords.binary_search(&end).unwrap();
+
+        if from < to {
+            self.update(1, 0, self.coords.len() - 1, from, to, delta);
+        }
+    }
+
+    // `node` spans the gaps lo..hi, i.e. coords[lo]..coords[hi]
+    fn update(&mut self, node: usize, lo: usize, hi: usize, from: usize, to: usize, delta: i32) {
+        if to <= lo || hi <= from {
+            return;
+        }
+
+        if from <= lo && hi <= to {
+            self.count[node] += delta;
+        } else {
+            let mid = (lo + hi) / 2;
+            self.update(2 * node, lo, mid, from, to, delta);
+            self.update(2 * node + 1, mid, hi, from, to, delta);
+        }
+
+        self.pull(node, lo, hi);
+    }
+
+    fn pull(&mut self, node: usize, lo: usize, hi: usize) {
+        let len = self.coords[hi] - self.coords[lo];
+        let leaf = hi - lo == 1;
+        let (left, right) = (2 * node, 2 * node + 1);
+
+        let children = |v: &Vec<u64>| if leaf { 0 } else { v[left] + v[right] };
+
+        match self.count[node] {
+            0 => {
+                self.once[node] = children(&self.once);
+                self.twice[node] = children(&self.twice);
+            }
+            1 => {
+                self.once[node] = len;
+                self.twice[node] = children(&self.once);
+            }
+            _ => {
+                self.once[node] = len;
+                self.twice[node] = len;
+            }
+        }
+    }
+}
+
+fn part2(claims: &[Claim]) -> Option<u32> {
+    ClaimIndex::new(claims).isolated().first().cloned()
+}
+
+/// Another claim that a claim shares fabric with
+#[derive(Debug, PartialEq, Clone, Copy)]
+struct Overlap {
+    id: u32,
+    area: u64,
+}
+
+/// Which claims overlap which, and by how much. Claims that only touch along
+/// an edge don't overlap.
+struct ClaimIndex {
+    ids: Vec<u32>, // in input order
+    positions: HashMap<u32, usize>,
+    overlaps: Vec<Vec<Overlap>>, // per claim, in input order
+}
+
+impl ClaimIndex {
+    fn new(claims: &[Claim]) -> ClaimIndex {
+        let mut overlaps: Vec<Vec<(usize, u64)>> = vec![Vec::new(); claims.len()];
+
+        // sweep from left to right, only comparing claims whose horizontal
+        // extents overlap
+        let mut order: Vec<usize> = (0..claims.len()).collect();
+        let rects: Vec<Rect> = claims.iter().map(Claim::rect).collect();
+        order.sort_by_key(|&i| rects[i].left);
+
+        let mut active: Vec<usize> = Vec::new();
+        for i in order {
+            active.retain(|&j| rects[j].right > rects[i].left);
+
+            for &j in &active {
+                if let Some(shared) = rects[i].intersection(&rects[j]) {
+                    let area = shared.area();
+                    overlaps[i].push((j, area));
+                    overlaps[j].push((i, area));
                 }
             }
+            active.push(i);
         }
-        if nonoverlapping {
-            return Some(claim.id);
+
+        let ids: Vec<u32> = claims.iter().map(|c| c.id).collect();
+        let overlaps = overlaps
+            .into_iter()
+            .map(|mut others| {
+                others.sort();
+                others
+                    .into_iter()
+                    .map(|(j, area)| Overlap { id: ids[j], area })
+                    .collect()
+            })
+            .collect();
+
+        ClaimIndex {
+            positions: ids.iter().enumerate().map(|(i, &id)| (id, i)).collect(),
+            ids,
+            overlaps,
         }
     }
 
-    None
+    /// The claims a claim overlaps, or `None` if there's no such claim
+    fn overlaps(&self, id: u32) -> Option<&[Overlap]> {
+        self.positions
+            .get(&id)
+            .map(|&i| self.overlaps[i].as_slice())
+    }
+
+    /// Claims that don't overlap any other claim, in input order
+    fn isolated(&self) -> Vec<u32> {
+        self.ids
+            .iter()
+            .zip(&self.overlaps)
+            .filter(|(_, others)| others.is_empty())
+            .map(|(&id, _)| id)
+            .collect()
+    }
+
+    /// Groups of claims that are linked by overlaps, ordered by their first
+    /// claim in the input. Isolated claims are groups of their own.
+    fn components(&self) -> Vec<Vec<u32>> {
+        let mut seen = vec![false; self.ids.len()];
+        let mut components = Vec::new();
+
+        for start in 0..self.ids.len() {
+            if seen[start] {
+                continue;
+            }
+
+            let mut component = Vec::new();
+            let mut queue: VecDeque<usize> = vec![start].into_iter().collect();
+            seen[start] = true;
+
+            while let Some(i) = queue.pop_front() {
+                component.push(i);
+                for overlap in &self.overlaps[i] {
+                    let j = self.positions[&overlap.id];
+                    if !seen[j] {
+                        seen[j] = true;
+                        queue.push_back(j);
+                    }
+                }
+            }
+
+            component.sort();
+            components.push(component.into_iter().map(|i| self.ids[i]).collect());
+        }
+
+        components
+    }
+}
+
+/// The fabric with claims being added and withdrawn one at a time. The
+/// number of contested squares and the claims that overlap no other claim
+/// are kept up to date as that happens. Both are worked out from the
+/// rectangles, so each change takes O(n log n) for n claims no matter how
+/// large they are.
+struct Fabric {
+    claims: HashMap<u32, Rect>,
+    overlapping: HashMap<u32, usize>, // other claims each claim overlaps
+    contested: u64,
+    uncontested: BTreeSet<u32>,
+}
+
+impl Fabric {
+    fn new() -> Fabric {
+        Fabric {
+            claims: HashMap::new(),
+            overlapping: HashMap::new(),
+            contested: 0,
+            uncontested: BTreeSet::new(),
+        }
+    }
+
+    /// Square inches covered by two or more claims
+    fn contested(&self) -> u64 {
+        self.contested
+    }
+
+    /// Ids of the claims that don't overlap any other claim
+    fn uncontested(&self) -> &BTreeSet<u32> {
+        &self.uncontested
+    }
+
+    /// Adds a claim, unless one with the same id is already on the fabric
+    fn add(&mut self, claim: &Claim) -> bool {
+        if self.claims.contains_key(&claim.id) {
+            return false;
+        }
+
+        let rect = claim.rect();
+        let mut shared = Vec::new();
+        for (&other, other_rect) in &self.claims {
+            if let Some(overlap) = rect.intersection(other_rect) {
+                shared.push(overlap);
+                *self.overlapping.get_mut(&other).unwrap() += 1;
+                self.uncontested.remove(&other);
+            }
+        }
+
+        if shared.is_empty() {
+            self.uncontested.insert(claim.id);
+        }
+        self.overlapping.insert(claim.id, shared.len());
+        self.claims.insert(claim.id, rect);
+
+        // squares only one other claim covered are now contested
+        self.contested += covered_exactly_once(&shared);
+
+        true
+    }
+
+    /// Takes a claim back off the fabric, if it's there
+    fn withdraw(&mut self, id: u32) -> bool {
+        let rect = match self.claims.remove(&id) {
+            Some(rect) => rect,
+            None => return false,
+        };
+
+        self.overlapping.remove(&id);
+        self.uncontested.remove(&id);
+
+        let mut shared = Vec::new();
+        for (&other, other_rect) in &self.claims {
+            if let Some(overlap) = rect.intersection(other_rect) {
+                shared.push(overlap);
+                let count = self.overlapping.get_mut(&other).unwrap();
+                *count -= 1;
+                if *count == 0 {
+                    self.uncontested.insert(other);
+                }
+            }
+        }
+
+        // squares only one other claim covers are no longer contested
+        self.contested -= covered_exactly_once(&shared);
+
+        true
+    }
+}
+
+/// Square inches covered by exactly one of the rectangles
+fn covered_exactly_once(rects: &[Rect]) -> u64 {
+    let (once, twice) = covered_area(rects);
+    once - twice
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// A deterministic jumble of claims small enough to also count densely
+    fn jumbled_claims() -> Vec<Claim> {
+        (0..200u32)
+            .map(|id| {
+                let r = id.wrapping_mul(2_654_435_761);
+                Claim {
+                    id,
+                    x: r % 97,
+                    y: (r >> 8) % 89,
+                    width: 1 + (r >> 16) % 13,
+                    height: 1 + (r >> 24) % 11,
+                }
+            })
+            .collect()
+    }
+
     #[test]
     fn day03_claim_parsing() {
         assert_eq!(
@@ -121,7 +599,15 @@ mod tests {
             }
         );
 
-        assert_eq!(Claim::from("some random string").is_err(), true);
+        assert!(Claim::from("some random string").is_err());
+    }
+
+    #[test]
+    fn day03_claim_serde() {
+        let claim = Claim::from("#27 @ 355,118: 12x15").unwrap();
+        let json = serde_json::to_string(&claim).unwrap();
+        assert_eq!(json, r#"{"id":27,"x":355,"y":118,"width":12,"height":15}"#);
+        assert_eq!(serde_json::from_str::<Claim>(&json).unwrap(), claim);
     }
 
     #[test]
@@ -135,6 +621,221 @@ mod tests {
         assert_eq!(part1(&claims), 4);
     }
 
+    #[test]
+    fn day03_overlap_area() {
+        let claims = vec![
+            Claim::from("#1 @ 1,3: 4x4").unwrap(),
+            Claim::from("#2 @ 3,1: 4x4").unwrap(),
+            Claim::from("#3 @ 5,5: 2x2").unwrap(),
+        ];
+        assert_eq!(overlap_area(&claims), 4);
+        assert_eq!(overlap_area(&[]), 0);
+
+        // three claims on top of each other still count once
+        let claims = vec![
+            Claim::from("#1 @ 0,0: 3x3").unwrap(),
+            Claim::from("#2 @ 0,0: 3x3").unwrap(),
+            Claim::from("#3 @ 1,1: 3x3").unwrap(),
+            Claim::from("#4 @ 9,9: 0x5").unwrap(),
+        ];
+        assert_eq!(overlap_area(&claims), 9);
+    }
+
+    #[test]
+    fn day03_overlap_area_matches_grid() {
+        let claims = jumbled_claims();
+
+        let dense = build_grid(&claims).to_matrix().count_where(|v| *v > 1) as u64;
+        assert_eq!(overlap_area(&claims), dense);
+        assert_eq!(part1(&claims), dense);
+    }
+
+    #[test]
+    fn day03_huge_fabric() {
+        let claims = vec![
+            Claim::from("#1 @ 1000000,2000000: 3000000x10").unwrap(),
+            Claim::from("#2 @ 3500000,2000005: 1000000x1000000").unwrap(),
+            Claim {
+                id: 3,
+                x: u32::MAX - 1,
+                y: u32::MAX - 1,
+                width: 4,
+                height: 4,
+            },
+        ];
+
+        assert!(fabric_area(&claims) > DENSE_LIMIT);
+        assert_eq!(part1(&claims), 500_000 * 5);
+    }
+
+    #[test]
+    fn day03_rect_touching_edges() {
+        let a = Rect::new(0, 0, 2, 2);
+        let right = Rect::new(2, 0, 2, 2);
+        let below = Rect::new(0, 2, 2, 2);
+        let corner = Rect::new(2, 2, 2, 2);
+
+        // sharing an edge or a corner isn't an overlap
+        for other in &[right, below, corner] {
+            assert!(!a.overlaps(other));
+            assert_eq!(a.intersection(other), None);
+        }
+
+        assert!(a.contains_point(1, 1));
+        assert!(!a.contains_point(2, 1));
+        assert!(!a.contains_point(1, 2));
+        assert!(right.contains_point(2, 1));
+    }
+
+    #[test]
+    fn day03_rect_overlapping() {
+        let a = Rect::new(1, 3, 4, 4);
+        let b = Rect::new(3, 1, 4, 4);
+
+        assert!(a.overlaps(&b));
+        assert!(b.overlaps(&a));
+        assert_eq!(a.intersection(&b), Some(Rect::new(3, 3, 2, 2)));
+        assert_eq!(a.intersection(&b).unwrap().area(), 4);
+
+        // one rectangle inside another
+        let inner = Rect::new(2, 4, 1, 1);
+        assert_eq!(a.intersection(&inner), Some(inner));
+
+        // empty rectangles never overlap anything
+        let empty = Rect::new(2, 4, 0, 3);
+        assert!(!a.overlaps(&empty));
+        assert_eq!(empty.area(), 0);
+    }
+
+    #[test]
+    fn day03_rect_large_values() {
+        let r = Rect::new(u32::MAX, u32::MAX, u32::MAX, u32::MAX);
+        assert_eq!(r.right, 2 * u64::from(u32::MAX));
+        assert!(r.contains_point(u64::from(u32::MAX) + 5, u64::from(u32::MAX)));
+
+        let huge = Rect {
+            left: 0,
+            top: 0,
+            right: u64::MAX,
+            bottom: u64::MAX,
+        };
+        assert_eq!(huge.area(), u64::MAX);
+        assert_eq!(union_area(&[huge, r]), u64::MAX);
+    }
+
+    #[test]
+    fn day03_union_area() {
+        let rects = vec![
+            Rect::new(1, 3, 4, 4),
+            Rect::new(3, 1, 4, 4),
+            Rect::new(5, 5, 2, 2),
+            Rect::new(7, 1, 1, 1),
+        ];
+        assert_eq!(union_area(&rects), 16 + 16 - 4 + 4 + 1);
+        assert_eq!(union_area(&[]), 0);
+    }
+
+    #[test]
+    fn day03_claim_index() {
+        let claims = vec![
+            Claim::from("#1 @ 1,3: 4x4").unwrap(),
+            Claim::from("#2 @ 3,1: 4x4").unwrap(),
+            Claim::from("#3 @ 5,5: 2x2").unwrap(),
+            Claim::from("#4 @ 4,4: 3x1").unwrap(),
+            Claim::from("#5 @ 7,1: 2x2").unwrap(),
+        ];
+        let index = ClaimIndex::new(&claims);
+
+        assert_eq!(
+            index.overlaps(1),
+            Some(&[Overlap { id: 2, area: 4 }, Overlap { id: 4, area: 1 }][..])
+        );
+        assert_eq!(
+            index.overlaps(4),
+            Some(&[Overlap { id: 1, area: 1 }, Overlap { id: 2, area: 3 }][..])
+        );
+        assert_eq!(index.overlaps(3), Some(&[][..]));
+        assert_eq!(index.overlaps(42), None);
+
+        // #3 only touches #4 along an edge, and #5 touches #2
+        assert_eq!(index.isolated(), vec![3, 5]);
+        assert_eq!(index.components(), vec![vec![1, 2, 4], vec![3], vec![5]]);
+    }
+
+    #[test]
+    fn day03_fabric() {
+        let claims = vec![
+            Claim::from("#1 @ 1,3: 4x4").unwrap(),
+            Claim::from("#2 @ 3,1: 4x4").unwrap(),
+            Claim::from("#3 @ 5,5: 2x2").unwrap(),
+        ];
+
+        let mut fabric = Fabric::new();
+        assert!(fabric.add(&claims[0]));
+        assert_eq!(fabric.contested(), 0);
+        assert_eq!(fabric.uncontested(), &vec![1].into_iter().collect());
+
+        assert!(fabric.add(&claims[1]));
+        assert!(fabric.add(&claims[2]));
+        assert!(!fabric.add(&claims[2]));
+        assert_eq!(fabric.contested(), part1(&claims));
+        assert_eq!(fabric.uncontested(), &vec![3].into_iter().collect());
+
+        // a claim that covers #3 and part of the contested square
+        let big = Claim::from("#4 @ 3,3: 4x4").unwrap();
+        assert!(fabric.add(&big));
+        assert_eq!(fabric.contested(), 4 + 4 + 4 + 4);
+        assert!(fabric.uncontested().is_empty());
+
+        assert!(fabric.withdraw(4));
+        assert!(!fabric.withdraw(4));
+        assert_eq!(fabric.contested(), 4);
+        assert_eq!(fabric.uncontested(), &vec![3].into_iter().collect());
+
+        assert!(fabric.withdraw(1));
+        assert_eq!(fabric.contested(), 0);
+        assert_eq!(fabric.uncontested(), &vec![2, 3].into_iter().collect());
+    }
+
+    #[test]
+    fn day03_fabric_matches_overlap_area() {
+        let claims = jumbled_claims();
+
+        let mut fabric = Fabric::new();
+        for claim in &claims {
+            fabric.add(claim);
+        }
+        assert_eq!(fabric.contested(), overlap_area(&claims));
+
+        for id in (0..200).step_by(3) {
+            fabric.withdraw(id);
+        }
+        let left: Vec<Claim> = claims.into_iter().filter(|c| c.id % 3 != 0).collect();
+        assert_eq!(fabric.contested(), overlap_area(&left));
+    }
+
+    #[test]
+    fn day03_huge_fabric_withdraw() {
+        let claims = vec![
+            Claim::from("#1 @ 1000000,2000000: 3000000x10").unwrap(),
+            Claim::from("#2 @ 3500000,2000005: 1000000x1000000").unwrap(),
+            Claim::from("#3 @ 0,0: 4000000000x4000000000").unwrap(),
+        ];
+
+        let mut fabric = Fabric::new();
+        for claim in &claims {
+            fabric.add(claim);
+        }
+        assert_eq!(
+            fabric.contested(),
+            3_000_000 * 10 + 1_000_000 * 1_000_000 - 500_000 * 5
+        );
+
+        assert!(fabric.withdraw(3));
+        assert_eq!(fabric.contested(), 500_000 * 5);
+        assert!(fabric.uncontested().is_empty());
+    }
+
     #[test]
     fn day03_part2() {
         let claims = vec![
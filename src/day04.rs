@@ -7,6 +7,7 @@ extern crate lazy_static;
 use chrono::{NaiveDate, NaiveDateTime, Timelike};
 use itertools::Itertools;
 use regex::Regex;
+use serde_derive::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 const INPUT: &str = include_str!("../input/day04.txt");
@@ -24,14 +25,14 @@ fn to_log_entries(input: &str) -> Vec<LogEntry> {
         .sorted_by_key(|entry| entry.date)
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
 enum GuardAction {
     Sleep,
     Awaken,
     NextGuard(usize), // id of the next guard
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
 struct LogEntry {
     date: NaiveDateTime,
     action: GuardAction,
@@ -176,14 +177,25 @@ mod tests {
         );
     }
 
+    #[test]
+    fn day04_entry_serde() {
+        let entries = to_log_entries(&test_data());
+        let json = serde_json::to_string(&entries[0]).unwrap();
+        assert_eq!(json, r#"{"date":"1518-11-01T00:00:00","action":{"NextGuard":10}}"#);
+        assert_eq!(serde_json::from_str::<LogEntry>(&json).unwrap(), entries[0]);
+
+        let json = serde_json::to_string(&entries[1]).unwrap();
+        assert_eq!(serde_json::from_str::<LogEntry>(&json).unwrap(), entries[1]);
+    }
+
     #[test]
     fn day04_build_sleeping_pattern() {
         let log_entries = to_log_entries(&test_data());
         let sleep_map = build_sleeping_pattern(&log_entries);
 
-        assert_eq!(sleep_map.contains_key(&10), true);
-        assert_eq!(sleep_map.contains_key(&99), true);
-        assert_eq!(sleep_map.contains_key(&999), false);
+        assert!(sleep_map.contains_key(&10));
+        assert!(sleep_map.contains_key(&99));
+        assert!(!sleep_map.contains_key(&999));
 
         let minute_history1 = sleep_map.get(&10).unwrap();
         assert_eq!(minute_history1[24], 2);
@@ -13,7 +13,7 @@ fn part1(input: &str) -> usize {
             stack.push(c);
         } else {
             if let Some(v) = stack.pop() {
-                if !(v != c && c.to_ascii_uppercase() == v.to_ascii_uppercase()) {
+                if !(v != c && c.eq_ignore_ascii_case(&v)) {
                     stack.push(v);
                     stack.push(c);
                 }
@@ -1,9 +1,14 @@
 use regex::Regex;
-use std::collections::HashMap;
+use serde_derive::{Deserialize, Serialize};
 
 #[macro_use]
 extern crate lazy_static;
 
+mod matrix;
+
+use matrix::region::label_components;
+use matrix::{Matrix, Pos};
+
 const INPUT: &str = include_str!("../input/day06.txt");
 
 fn main() {
@@ -12,7 +17,7 @@ fn main() {
     println!("Part2: {}", part2(&points, 10000));
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
 struct Point(usize, usize);
 
 impl Point {
@@ -32,7 +37,7 @@ impl Point {
     }
 
     /// returns the index of the point that closest to the current point
-    fn closest_to(&self, points: &Vec<Point>) -> Option<usize> {
+    fn closest_to(&self, points: &[Point]) -> Option<usize> {
         let distances: Vec<_> = points.iter().map(|p| self.dist(p)).collect();
         let (closest_index, min_dist) = distances
             .iter()
@@ -49,12 +54,12 @@ impl Point {
     }
 
     /// returns the total distance from all the points
-    fn total_distance(&self, points: &Vec<Point>) -> i32 {
+    fn total_distance(&self, points: &[Point]) -> i32 {
         points.iter().map(|p| self.dist(p)).sum()
     }
 }
 
-fn grid_size(points: &Vec<Point>) -> (usize, usize, usize, usize) {
+fn grid_size(points: &[Point]) -> (usize, usize, usize, usize) {
     let max_x = points.iter().map(|p| p.0).max().unwrap();
     let max_y = points.iter().map(|p| p.1).max().unwrap();
     let min_x = points.iter().map(|p| p.0).min().unwrap();
@@ -67,43 +72,28 @@ fn to_points(s: &str) -> Vec<Point> {
     s.lines().map(Point::from_str).collect()
 }
 
-fn part1(points: &Vec<Point>) -> usize {
+fn part1(points: &[Point]) -> usize {
     let (min_x, max_x, min_y, max_y) = grid_size(points);
 
-    let mut map: HashMap<usize, usize> = HashMap::new();
-
-    for i in min_x..max_x {
-        for j in min_y..max_y {
-            let point = Point(i, j);
-            if let Some(closest_index) = point.closest_to(&points) {
-                let entry = map.entry(closest_index).or_insert(0);
-                *entry += 1;
-            }
-        }
-    }
-
-    // remove values for boundary points on x axis
-    for x in min_x..max_x {
-        for y in &[min_y, max_y] {
-            if let Some(closest_index) = Point(x, *y).closest_to(&points) {
-                map.remove(&closest_index);
-            }
+    // mark each cell of the bounding box with the point it's closest to
+    let mut grid = Matrix::new(max_y - min_y + 1, max_x - min_x + 1, None);
+    for y in min_y..=max_y {
+        for x in min_x..=max_x {
+            grid[Pos::new(x - min_x, y - min_y)] = Point(x, y).closest_to(points);
         }
     }
 
-    // remove values for boundary points on y axis
-    for y in min_y..max_y {
-        for x in &[min_x, max_x] {
-            if let Some(closest_index) = Point(*x, y).closest_to(&points) {
-                map.remove(&closest_index);
-            }
-        }
-    }
-
-    *map.values().max().unwrap()
+    // an area that reaches the edge of the bounding box goes on forever
+    label_components(&grid, |closest| *closest)
+        .regions
+        .iter()
+        .filter(|region| !region.touches_border)
+        .map(|region| region.size)
+        .max()
+        .unwrap()
 }
 
-fn part2(points: &Vec<Point>, within_area: i32) -> usize {
+fn part2(points: &[Point], within_area: i32) -> usize {
     let (min_x, max_x, min_y, max_y) = grid_size(points);
 
     let mut area = 0;
@@ -111,7 +101,7 @@ fn part2(points: &Vec<Point>, within_area: i32) -> usize {
     for i in min_x..max_x {
         for j in min_y..max_y {
             let point = Point(i, j);
-            if point.total_distance(&points) < within_area {
+            if point.total_distance(points) < within_area {
                 area += 1;
             }
         }
@@ -130,6 +120,13 @@ mod tests {
         assert_eq!(p, Point(20, 44));
     }
 
+    #[test]
+    fn day06_point_serde() {
+        let json = serde_json::to_string(&Point(20, 44)).unwrap();
+        assert_eq!(json, "[20,44]");
+        assert_eq!(serde_json::from_str::<Point>(&json).unwrap(), Point(20, 44));
+    }
+
     #[test]
     fn day06_grid_size() {
         let points = get_points();
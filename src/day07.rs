@@ -1,4 +1,5 @@
 use regex::Regex;
+use serde_derive::{Deserialize, Serialize};
 use std::collections::HashSet;
 #[macro_use]
 extern crate lazy_static;
@@ -11,6 +12,7 @@ fn main() {
 
 type Step = char;
 
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
 struct Dep {
     step: Step,
     requirement: Step,
@@ -31,13 +33,13 @@ impl Dep {
     }
 }
 
-fn part1(deps: &Vec<Dep>) -> String {
-    let steps = get_sorted_steps(&deps);
+fn part1(deps: &[Dep]) -> String {
+    let steps = get_sorted_steps(deps);
     let mut order = vec![];
     let mut seen = HashSet::new();
 
     loop {
-        match unvisited_steps(&steps, &deps, &seen).first() {
+        match unvisited_steps(&steps, deps, &seen).first() {
             None => break,
             Some(next_step) => {
                 order.push(*next_step);
@@ -49,7 +51,7 @@ fn part1(deps: &Vec<Dep>) -> String {
     order.into_iter().collect()
 }
 
-fn get_sorted_steps(deps: &Vec<Dep>) -> Vec<Step> {
+fn get_sorted_steps(deps: &[Dep]) -> Vec<Step> {
     let mut steps: Vec<_> = deps
         .iter()
         .flat_map(|dep| vec![dep.step, dep.requirement].into_iter())
@@ -62,9 +64,9 @@ fn get_sorted_steps(deps: &Vec<Dep>) -> Vec<Step> {
     steps
 }
 
-fn unvisited_steps(steps: &Vec<Step>, deps: &Vec<Dep>, visited: &HashSet<Step>) -> Vec<Step> {
+fn unvisited_steps(steps: &[Step], deps: &[Dep], visited: &HashSet<Step>) -> Vec<Step> {
     steps
-        .into_iter()
+        .iter()
         .filter(|id| !visited.contains(id)) // not yet visited
         .filter(|id| {
             // all deps have been visited
@@ -72,7 +74,7 @@ fn unvisited_steps(steps: &Vec<Step>, deps: &Vec<Dep>, visited: &HashSet<Step>)
                 .filter(|d| d.step == **id)
                 .all(|d| visited.contains(&d.requirement))
         })
-        .map(|c| *c)
+        .copied()
         .collect()
 }
 
@@ -87,6 +89,14 @@ mod tests {
         assert_eq!(dep.requirement, 'C');
     }
 
+    #[test]
+    fn day07_dep_serde() {
+        let dep = Dep::from_str("Step C must be finished before step A can begin.");
+        let json = serde_json::to_string(&dep).unwrap();
+        assert_eq!(json, r#"{"step":"A","requirement":"C"}"#);
+        assert_eq!(serde_json::from_str::<Dep>(&json).unwrap(), dep);
+    }
+
     #[test]
     fn day07_sorted_ids() {
         let deps = to_deps();
@@ -1,9 +1,12 @@
 use regex::Regex;
+use serde_derive::{Deserialize, Serialize};
 #[macro_use]
 extern crate lazy_static;
 
 mod matrix;
 
+use matrix::{Grid, Pos, SparseGrid};
+
 const INPUT: &str = include_str!("../input/day10.txt");
 
 fn main() {
@@ -35,7 +38,7 @@ fn main() {
     }
 }
 
-fn tick_points(points: &Vec<Point>, time: i32) -> Vec<Point> {
+fn tick_points(points: &[Point], time: i32) -> Vec<Point> {
     points
         .iter()
         .map(|point| Point {
@@ -48,34 +51,24 @@ fn tick_points(points: &Vec<Point>, time: i32) -> Vec<Point> {
         .collect()
 }
 
-fn area_of_grid(points: &Vec<Point>) -> i64 {
-    let (Vector(xmin, xmax), Vector(ymin, ymax)) = grid_size(&points);
+fn area_of_grid(points: &[Point]) -> i64 {
+    let (Vector(xmin, xmax), Vector(ymin, ymax)) = grid_size(points);
 
     (ymax - ymin) as i64 * (xmax - xmin) as i64
 }
 
-fn draw_grid(points: &Vec<Point>) -> String {
-    let (Vector(xmin, xmax), Vector(ymin, ymax)) = grid_size(&points);
-
-    let mut grid = matrix::Matrix::new((ymax - ymin + 1) as usize, (xmax - xmin + 1) as usize, '.');
+fn draw_grid(points: &[Point]) -> String {
+    let mut grid = SparseGrid::new();
 
     for point in points {
         let pos = &point.position;
-        grid[((pos.1 - ymin) as usize, (pos.0 - xmin) as usize)] = '#';
+        grid.set(Pos::new(pos.0 as isize, pos.1 as isize).into(), '#');
     }
 
-    let result = grid
-        .rows()
-        .map(|row| {
-            let r = row.iter().map(|c| c.to_string()).collect::<String>();
-            format!("{}\n", r)
-        })
-        .collect::<String>();
-
-    result.trim().to_string()
+    grid.render(|c| if c.is_some() { '#' } else { '.' })
 }
 
-fn grid_size(points: &Vec<Point>) -> (Vector, Vector) {
+fn grid_size(points: &[Point]) -> (Vector, Vector) {
     let mut xs: Vec<_> = points.iter().map(|p| p.position.0).collect();
     let mut ys: Vec<_> = points.iter().map(|p| p.position.1).collect();
 
@@ -88,10 +81,10 @@ fn grid_size(points: &Vec<Point>) -> (Vector, Vector) {
     )
 }
 
-#[derive(Debug, PartialEq, Clone, Copy)]
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
 struct Vector(i32, i32);
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
 struct Point {
     position: Vector,
     velocity: Vector,
@@ -132,6 +125,14 @@ mod tests {
         assert_eq!(point.velocity, Vector(-2, 1));
     }
 
+    #[test]
+    fn day10_point_serde() {
+        let point = Point::from_str("position=< 20416, -10005> velocity=<-2,  1>");
+        let json = serde_json::to_string(&point).unwrap();
+        assert_eq!(json, r#"{"position":[20416,-10005],"velocity":[-2,1]}"#);
+        assert_eq!(serde_json::from_str::<Point>(&json).unwrap(), point);
+    }
+
     #[test]
     fn day10_test_grid_size() {
         let points = get_test_data();
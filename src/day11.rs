@@ -1,18 +1,22 @@
 mod matrix;
 
-type Point = (usize, usize);
+use matrix::{Pos, RowCol};
+
+type Point = Pos;
 type Grid = matrix::Matrix<i32>;
 
 fn main() {
     let serial_number = 6392;
     let area_table = summed_area_table(&build_grid(serial_number));
-    println!("Part 1: {:?}", part1(&area_table, 3).0);
-    println!("Part 2: {:?}", part2(&area_table));
+    let (p, _) = part1(&area_table, 3);
+    println!("Part 1: {:?}", (p.x, p.y));
+    let (p, size) = part2(&area_table);
+    println!("Part 2: {:?}", ((p.x, p.y), size));
 }
 
 fn part2(area_table: &Grid) -> (Point, usize) {
     let mut best_size = 1;
-    let mut best_point = (0, 0);
+    let mut best_point = Pos::new(0, 0);
     let mut max_power = 0;
 
     for s in 1..300 {
@@ -32,20 +36,22 @@ fn part2(area_table: &Grid) -> (Point, usize) {
 fn summed_area_table(m: &Grid) -> Grid {
     let mut table = matrix::Matrix::new(m.height, m.width, 0);
 
-    table[(0, 0)] = m[(0, 0)];
+    let at = RowCol::new;
+
+    table[at(0, 0)] = m[at(0, 0)];
 
-    for i in 1..table.width {
-        table[(i, 0)] = m[(i, 0)] + table[(i - 1, 0)];
+    for i in 1..table.height {
+        table[at(i, 0)] = m[at(i, 0)] + table[at(i - 1, 0)];
     }
 
-    for j in 1..table.height {
-        table[(0, j)] = m[(0, j)] + table[(0, j - 1)];
+    for j in 1..table.width {
+        table[at(0, j)] = m[at(0, j)] + table[at(0, j - 1)];
     }
 
-    for i in 1..table.width {
-        for j in 1..table.height {
-            table[(i, j)] =
-                m[(i, j)] + table[(i - 1, j)] + table[(i, j - 1)] - table[(i - 1, j - 1)];
+    for i in 1..table.height {
+        for j in 1..table.width {
+            table[at(i, j)] =
+                m[at(i, j)] + table[at(i - 1, j)] + table[at(i, j - 1)] - table[at(i - 1, j - 1)];
         }
     }
 
@@ -54,13 +60,13 @@ fn summed_area_table(m: &Grid) -> Grid {
 
 fn part1(area_table: &Grid, size: usize) -> (Point, i32) {
     let mut max_power = 0;
-    let mut max_cell = (0, 0);
+    let mut max_cell = Pos::new(0, 0);
 
-    for i in 0..(300 - size) {
-        for j in 0..(300 - size) {
-            let power = total_power((i, j), &area_table, size);
+    for x in 0..(300 - size) {
+        for y in 0..(300 - size) {
+            let power = total_power(Pos::new(x, y), area_table, size);
             if power > max_power {
-                max_cell = (i, j);
+                max_cell = Pos::new(x, y);
                 max_power = power;
             }
         }
@@ -70,37 +76,29 @@ fn part1(area_table: &Grid, size: usize) -> (Point, i32) {
 }
 
 fn build_grid(serial_number: i32) -> Grid {
-    let mut m = matrix::Matrix::new(300, 300, 0);
-
-    for i in 0..300 {
-        for j in 0..300 {
-            m[(i, j)] = power_level((i, j), serial_number);
-        }
-    }
-
-    m
+    matrix::Matrix::from_fn(300, 300, |s| power_level(Pos::from(s), serial_number))
 }
 
 /// Calculates the total area of a summed area table as per formula in
 /// https://en.wikipedia.org/wiki/Summed-area_table
 /// The variable names A, B, C, D correspond to the algorithm shown above
 fn total_power(point: Point, summed_table: &Grid, size: usize) -> i32 {
-    let (i, j) = point;
-    let d = summed_table[(i + size - 1, j + size - 1)];
-    let a = if (i as isize) - 1 >= 0 && (j as isize) - 1 >= 0 {
-        summed_table[(i - 1, j - 1)]
+    let Pos { x, y } = point;
+    let d = summed_table[Pos::new(x + size - 1, y + size - 1)];
+    let a = if x > 0 && y > 0 {
+        summed_table[Pos::new(x - 1, y - 1)]
     } else {
         0
     };
 
-    let b = if (j as isize) - 1 >= 0 {
-        summed_table[(i + size - 1, j - 1)]
+    let b = if y > 0 {
+        summed_table[Pos::new(x + size - 1, y - 1)]
     } else {
         0
     };
 
-    let c = if (i as isize) - 1 >= 0 {
-        summed_table[(i - 1, j + size - 1)]
+    let c = if x > 0 {
+        summed_table[Pos::new(x - 1, y + size - 1)]
     } else {
         0
     };
@@ -109,7 +107,7 @@ fn total_power(point: Point, summed_table: &Grid, size: usize) -> i32 {
 }
 
 fn power_level(point: Point, serial_number: i32) -> i32 {
-    let (x, y) = point;
+    let Pos { x, y } = point;
     let rack_id = (x as i32) + 10;
     let j = ((rack_id * y as i32) + serial_number) * rack_id;
 
@@ -126,52 +124,45 @@ mod tests {
 
     #[test]
     fn day11_power_level() {
-        assert_eq!(power_level((3, 5), 8), 4);
-        assert_eq!(power_level((122, 79), 57), -5);
-        assert_eq!(power_level((217, 196), 39), 0);
-        assert_eq!(power_level((101, 153), 71), 4);
+        assert_eq!(power_level(Pos::new(3, 5), 8), 4);
+        assert_eq!(power_level(Pos::new(122, 79), 57), -5);
+        assert_eq!(power_level(Pos::new(217, 196), 39), 0);
+        assert_eq!(power_level(Pos::new(101, 153), 71), 4);
     }
 
     #[test]
     fn day11_build_grid() {
         let m = build_grid(18);
-        assert_eq!(m[(33, 45)], 4);
-        assert_eq!(m[(34, 45)], 4);
-        assert_eq!(m[(35, 45)], 4);
-        assert_eq!(m[(35, 46)], 4);
-        assert_eq!(m[(35, 47)], 4);
-        assert_eq!(m[(33, 46)], 3);
-        assert_eq!(m[(33, 47)], 1);
+        assert_eq!(m[Pos::new(33, 45)], 4);
+        assert_eq!(m[Pos::new(34, 45)], 4);
+        assert_eq!(m[Pos::new(35, 45)], 4);
+        assert_eq!(m[Pos::new(35, 46)], 4);
+        assert_eq!(m[Pos::new(35, 47)], 4);
+        assert_eq!(m[Pos::new(33, 46)], 3);
+        assert_eq!(m[Pos::new(33, 47)], 1);
     }
 
     #[test]
     fn day11_total_power() {
         let area_table = summed_area_table(&build_grid(18));
-        assert_eq!(total_power((33, 45), &area_table, 3), 29);
+        assert_eq!(total_power(Pos::new(33, 45), &area_table, 3), 29);
     }
 
     #[test]
     fn day11_part1() {
         let area_table = summed_area_table(&build_grid(6392));
-        assert_eq!(part1(&area_table, 3).0, (20, 58));
+        assert_eq!(part1(&area_table, 3).0, Pos::new(20, 58));
     }
 
     #[test]
     fn day11_part2() {
         let area_table = summed_area_table(&build_grid(6392));
-        assert_eq!(part2(&area_table), ((233, 268), 13));
+        assert_eq!(part2(&area_table), (Pos::new(233, 268), 13));
     }
 
     #[test]
     fn day11_summed_area_table() {
-        let mut m = matrix::Matrix::new(3, 3, 0);
-        let v = vec![5, 2, 3, 1, 5, 4, 2, 2, 1];
-
-        for i in 0..3 {
-            for j in 0..3 {
-                m[(i, j)] = v[i * 3 + j];
-            }
-        }
+        let m = matrix::Matrix::from_vec(3, 3, vec![5, 2, 3, 1, 5, 4, 2, 2, 1]).unwrap();
 
         let summed_table = summed_area_table(&m);
         assert_eq!(
@@ -14,8 +14,8 @@ fn part1(max_r: usize) -> String {
         j = (j + 1 + recipies[j]) % recipies.len();
     }
 
-    (&recipies[max_r..(max_r + 10)])
-        .into_iter()
+    recipies[max_r..(max_r + 10)]
+        .iter()
         .map(|x| x.to_string())
         .collect()
 }
@@ -31,12 +31,11 @@ fn part2(target: &[usize]) -> usize {
         j = (j + 1 + recipies[j]) % recipies.len();
 
         for offset in 0..=1 {
-            if recipies.len() - offset >= target.len() {
-                if &recipies[recipies.len() - target.len() - offset..recipies.len() - offset]
+            if recipies.len() - offset >= target.len()
+                && &recipies[recipies.len() - target.len() - offset..recipies.len() - offset]
                     == target
-                {
-                    return recipies.len() - target.len() - offset;
-                }
+            {
+                return recipies.len() - target.len() - offset;
             }
         }
     }
@@ -50,7 +49,7 @@ fn digits(x: usize) -> Vec<usize> {
     let mut digits = vec![];
     while number > 0 {
         digits.push(number % 10);
-        number = number / 10;
+        number /= 10;
     }
 
     digits.reverse();
@@ -73,10 +72,10 @@ mod tests {
 
     #[test]
     fn day14_part2() {
-        assert_eq!(part2(&vec![5, 1, 5, 8, 9]), 9);
-        assert_eq!(part2(&vec![0, 1, 2, 4, 5]), 5);
-        assert_eq!(part2(&vec![9, 2, 5, 1, 0]), 18);
-        assert_eq!(part2(&vec![5, 9, 4, 1, 4]), 2018);
+        assert_eq!(part2(&[5, 1, 5, 8, 9]), 9);
+        assert_eq!(part2(&[0, 1, 2, 4, 5]), 5);
+        assert_eq!(part2(&[9, 2, 5, 1, 0]), 18);
+        assert_eq!(part2(&[5, 9, 4, 1, 4]), 2018);
     }
 
 }
@@ -1,19 +1,90 @@
 // A 2D matrix implementation that's implemented with a 1D vector
 
+// Every binary that needs a grid compiles this module, and none of them uses
+// all of it, so the submodules and re-exports allow being unused.
+
+#[allow(dead_code)]
+pub mod automaton;
+pub mod bits;
+pub mod convolve;
+pub mod coord;
+#[allow(dead_code)]
+pub mod grid;
+#[allow(dead_code)]
+pub mod gridn;
+#[allow(dead_code)]
+pub mod image;
+#[allow(dead_code)]
+pub mod offset;
+pub mod ops;
+#[cfg(feature = "parallel")]
+pub mod parallel;
+#[allow(dead_code)]
+pub mod pathfinding;
+#[allow(dead_code)]
+pub mod region;
+#[allow(dead_code)]
+pub mod sparse;
+#[cfg(test)]
+mod testing;
+pub mod transform;
+pub mod view;
+
+#[allow(unused_imports)]
+pub use self::bits::BitMatrix;
+#[allow(unused_imports)]
+pub use self::convolve::BorderMode;
+#[allow(unused_imports)]
+pub use self::coord::{Pos, RowCol};
+#[allow(unused_imports)]
+pub use self::grid::{Cells, Grid};
+#[allow(unused_imports)]
+pub use self::gridn::{Grid3, GridN};
+#[allow(unused_imports)]
+pub use self::offset::OffsetMatrix;
+#[allow(unused_imports)]
+pub use self::sparse::SparseGrid;
+#[allow(unused_imports)]
+pub use self::view::{SubMatrix, SubMatrixMut};
+
+use serde_derive::{Deserialize, Serialize};
+use std::convert::TryFrom;
 use std::fmt::Debug;
 use std::fmt::Error;
 use std::fmt::Formatter;
 use std::ops::{Index, IndexMut};
 
+#[derive(Clone, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(try_from = "MatrixData<T>")]
 pub struct Matrix<T> {
     buf: Vec<T>,
     pub height: usize,
     pub width: usize,
 }
 
+/// A deserialized matrix whose dimensions haven't been checked yet
+#[derive(Deserialize)]
+struct MatrixData<T> {
+    buf: Vec<T>,
+    height: usize,
+    width: usize,
+}
+
+impl<T> TryFrom<MatrixData<T>> for Matrix<T> {
+    type Error = String;
+
+    fn try_from(data: MatrixData<T>) -> Result<Matrix<T>, String> {
+        let MatrixData { buf, height, width } = data;
+        let len = buf.len();
+
+        Matrix::from_vec(height, width, buf)
+            .ok_or_else(|| format!("expected {}x{} values, found {}", height, width, len))
+    }
+}
+
 impl<T> Matrix<T>
 where
-    T: Copy,
+    T: Clone,
 {
     /// Creates a new matrix with an initial value of height and width
     pub fn new(height: usize, width: usize, initial: T) -> Matrix<T> {
@@ -26,32 +97,133 @@ where
 }
 
 impl<T> Matrix<T> {
-    fn index_of(&self, s: (usize, usize)) -> usize {
-        assert!(s.0 < self.height);
-        assert!(s.1 < self.width);
+    /// Creates a new matrix where every cell is computed from its (row, col)
+    pub fn from_fn<F>(height: usize, width: usize, f: F) -> Matrix<T>
+    where
+        F: FnMut(RowCol) -> T,
+    {
+        Matrix {
+            height,
+            width,
+            buf: (0..height)
+                .flat_map(|i| (0..width).map(move |j| RowCol::new(i, j)))
+                .map(f)
+                .collect(),
+        }
+    }
 
-        s.0 * self.width + s.1
+    /// Creates a matrix from its values in reading order. Returns None if
+    /// there aren't exactly height * width of them.
+    pub fn from_vec(height: usize, width: usize, buf: Vec<T>) -> Option<Matrix<T>> {
+        if buf.len() == height * width {
+            Some(Matrix { buf, height, width })
+        } else {
+            None
+        }
+    }
+
+    fn index_of(&self, s: RowCol) -> usize {
+        assert!(s.row < self.height);
+        assert!(s.col < self.width);
+
+        s.row * self.width + s.col
+    }
+
+    /// The orthogonal neighbors of a position that lie inside the matrix,
+    /// in reading order (up, left, right, down)
+    pub fn neighbors(&self, s: RowCol) -> impl Iterator<Item = RowCol> {
+        let (height, width) = (self.height, self.width);
+        let (i, j) = (s.row as isize, s.col as isize);
+
+        vec![(i - 1, j), (i, j - 1), (i, j + 1), (i + 1, j)]
+            .into_iter()
+            .filter(move |(i, j)| {
+                *i >= 0 && *j >= 0 && (*i as usize) < height && (*j as usize) < width
+            })
+            .map(|(i, j)| RowCol::new(i as usize, j as usize))
+    }
+
+    /// All eight neighbors of a position (including diagonals) that lie
+    /// inside the matrix, in reading order
+    pub fn surrounding(&self, s: RowCol) -> impl Iterator<Item = RowCol> {
+        let (height, width) = (self.height, self.width);
+        let (i, j) = (s.row as isize, s.col as isize);
+
+        (i - 1..=i + 1)
+            .flat_map(move |y| (j - 1..=j + 1).map(move |x| (y, x)))
+            .filter(move |(y, x)| {
+                (*y, *x) != (i, j)
+                    && *y >= 0
+                    && *x >= 0
+                    && (*y as usize) < height
+                    && (*x as usize) < width
+            })
+            .map(|(y, x)| RowCol::new(y as usize, x as usize))
     }
 
     pub fn rows(&self) -> impl Iterator<Item = Vec<&T>> {
         let width = self.width;
 
-        (0..self.height).map(move |y| (0..width).map(|x| &self[(y, x)]).collect())
+        (0..self.height).map(move |y| (0..width).map(|x| &self[RowCol::new(y, x)]).collect())
+    }
+}
+
+/// An inclusive bounding box of signed positions
+#[allow(dead_code)]
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Bounds {
+    pub min: RowCol<isize>,
+    pub max: RowCol<isize>,
+}
+
+#[allow(dead_code)]
+impl Bounds {
+    /// A bounding box covering just the one position
+    pub fn at(pos: RowCol<isize>) -> Bounds {
+        Bounds { min: pos, max: pos }
+    }
+
+    pub fn height(&self) -> usize {
+        (self.max.row - self.min.row + 1) as usize
+    }
+
+    pub fn width(&self) -> usize {
+        (self.max.col - self.min.col + 1) as usize
+    }
+
+    pub fn contains(&self, pos: RowCol<isize>) -> bool {
+        self.min.row <= pos.row
+            && pos.row <= self.max.row
+            && self.min.col <= pos.col
+            && pos.col <= self.max.col
+    }
+
+    /// Grows the bounding box so that it covers `pos`
+    pub fn include(&mut self, pos: RowCol<isize>) {
+        self.min = RowCol::new(self.min.row.min(pos.row), self.min.col.min(pos.col));
+        self.max = RowCol::new(self.max.row.max(pos.row), self.max.col.max(pos.col));
     }
 }
 
-impl<T> Index<(usize, usize)> for Matrix<T> {
+/// Matrices are indexed by `RowCol` or `Pos`, never by a bare tuple
+impl<T, S> Index<S> for Matrix<T>
+where
+    S: Into<RowCol>,
+{
     type Output = T;
 
-    fn index(&self, s: (usize, usize)) -> &T {
-        let i = self.index_of(s);
+    fn index(&self, s: S) -> &T {
+        let i = self.index_of(s.into());
         &self.buf[i]
     }
 }
 
-impl<T> IndexMut<(usize, usize)> for Matrix<T> {
-    fn index_mut(&mut self, s: (usize, usize)) -> &mut T {
-        let i = self.index_of(s);
+impl<T, S> IndexMut<S> for Matrix<T>
+where
+    S: Into<RowCol>,
+{
+    fn index_mut(&mut self, s: S) -> &mut T {
+        let i = self.index_of(s.into());
         &mut self.buf[i]
     }
 }
@@ -65,7 +237,7 @@ where
         for y in 0..self.height {
             write!(f, "[")?;
             for x in 0..self.width {
-                write!(f, "{:?}", self[(y, x)])?;
+                write!(f, "{:?}", self[RowCol::new(y, x)])?;
                 if x < self.width - 1 {
                     write!(f, ", ")?;
                 }
@@ -81,19 +253,20 @@ where
 
 #[cfg(test)]
 mod test {
+    use super::testing::tuples;
     use super::*;
 
     #[test]
     fn test_matrix() {
         let mut m = Matrix::new(3, 4, 4.0);
-        m[(0, 0)] = 33.0;
-        m[(1, 0)] = 22.0;
-        m[(1, 2)] = 11.0;
+        m[RowCol::new(0, 0)] = 33.0;
+        m[RowCol::new(1, 0)] = 22.0;
+        m[RowCol::new(1, 2)] = 11.0;
 
-        assert_eq!(m[(1, 1)], 4.0);
-        assert_eq!(m[(0, 0)], 33.0);
-        assert_eq!(m[(1, 0)], 22.0);
-        assert_eq!(m[(1, 2)], 11.0);
+        assert_eq!(m[RowCol::new(1, 1)], 4.0);
+        assert_eq!(m[RowCol::new(0, 0)], 33.0);
+        assert_eq!(m[RowCol::new(1, 0)], 22.0);
+        assert_eq!(m[RowCol::new(1, 2)], 11.0);
 
         assert_eq!(
             format!("{:?}", m),
@@ -106,11 +279,97 @@ mod test {
     #[test]
     fn test_matrix_iter() {
         let m = Matrix::new(3, 3, 5);
-        let total = m
-            .rows()
-            .flat_map(|r| r.into_iter())
-            .fold(0, |acc, val| acc + val);
+        let total = m.rows().flat_map(|r| r.into_iter()).sum::<i32>();
 
         assert_eq!(total, 3 * 3 * 5);
     }
+
+    #[test]
+    fn test_matrix_from_fn() {
+        let m = Matrix::from_fn(2, 3, |s| format!("{}{}", s.row, s.col));
+        assert_eq!(m[RowCol::new(1, 2)], "12");
+        assert_eq!(
+            format!("{:?}", m),
+            r#"[["00", "01", "02"]
+ ["10", "11", "12"]]"#
+        );
+    }
+
+    #[test]
+    fn test_matrix_from_vec() {
+        let m = Matrix::from_vec(2, 2, vec![1, 2, 3, 4]).unwrap();
+        assert_eq!(m[RowCol::new(1, 0)], 3);
+        assert!(Matrix::from_vec(2, 2, vec![1, 2, 3]).is_none());
+    }
+
+    #[test]
+    fn test_matrix_clone_eq_hash() {
+        use std::collections::HashSet;
+
+        let mut m = Matrix::new(2, 2, vec![0usize]);
+        let copy = m.clone();
+        assert!(m == copy);
+
+        m[RowCol::new(0, 1)].push(1);
+        assert!(m != copy);
+        assert_eq!(copy[RowCol::new(0, 1)], vec![0]);
+
+        let mut seen = HashSet::new();
+        assert!(seen.insert(copy.clone()));
+        assert!(seen.insert(m));
+        assert!(!seen.insert(copy));
+
+        // same cells, different shape
+        let wide = Matrix::new(1, 4, 0);
+        let tall = Matrix::new(4, 1, 0);
+        assert!(wide != tall);
+    }
+
+    #[test]
+    fn test_matrix_pos_index() {
+        let mut m = Matrix::new(2, 3, 0);
+        m[Pos::new(2, 1)] = 7;
+
+        assert_eq!(m[RowCol::new(1, 2)], 7);
+        assert_eq!(m[Pos { x: 2, y: 1 }], 7);
+    }
+
+    #[test]
+    fn test_matrix_serde() {
+        let m = Matrix::from_vec(2, 2, vec![1, 2, 3, 4]).unwrap();
+        let json = serde_json::to_string(&m).unwrap();
+        assert_eq!(json, r#"{"buf":[1,2,3,4],"height":2,"width":2}"#);
+
+        let back: Matrix<i32> = serde_json::from_str(&json).unwrap();
+        assert!(back == m);
+
+        let bad = serde_json::from_str::<Matrix<i32>>(r#"{"buf":[1,2,3],"height":2,"width":2}"#);
+        assert!(bad.is_err());
+    }
+
+    #[test]
+    fn test_matrix_neighbors() {
+        let m = Matrix::new(3, 4, 0);
+        let corner = tuples(m.neighbors(RowCol::new(0, 0)));
+        assert_eq!(corner, vec![(0, 1), (1, 0)]);
+
+        let middle = tuples(m.neighbors(RowCol::new(1, 2)));
+        assert_eq!(middle, vec![(0, 2), (1, 1), (1, 3), (2, 2)]);
+
+        let edge = tuples(m.neighbors(RowCol::new(2, 3)));
+        assert_eq!(edge, vec![(1, 3), (2, 2)]);
+    }
+
+    #[test]
+    fn test_matrix_surrounding() {
+        let m = Matrix::new(3, 4, 0);
+        let corner = tuples(m.surrounding(RowCol::new(0, 0)));
+        assert_eq!(corner, vec![(0, 1), (1, 0), (1, 1)]);
+
+        let middle = tuples(m.surrounding(RowCol::new(1, 2)));
+        assert_eq!(middle.len(), 8);
+        assert_eq!(middle.first(), Some(&(0, 1)));
+        assert_eq!(middle.last(), Some(&(2, 3)));
+        assert!(!middle.contains(&(1, 2)));
+    }
 }
@@ -0,0 +1,184 @@
+// A cellular automaton over a matrix. Every generation each cell is
+// replaced by applying a rule to the cell and its (up to eight) surrounding
+// cells. Every state is remembered, so once one repeats the automaton knows
+// it's in a cycle and can skip ahead to any later generation.
+
+use super::{Matrix, RowCol};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::mem;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Cycle {
+    pub start: usize,  // first generation that is part of the cycle
+    pub length: usize, // generations before the state repeats
+}
+
+pub struct Automaton<T, F> {
+    current: Matrix<T>,
+    next: Matrix<T>,
+    rule: F,
+    generation: usize,
+    history: Vec<Matrix<T>>,        // the state of every generation so far
+    seen: HashMap<u64, Vec<usize>>, // state hash -> generations with that hash
+    cycle: Option<Cycle>,
+}
+
+impl<T, F> Automaton<T, F>
+where
+    T: Clone + Eq + Hash,
+    F: Fn(&T, &[&T]) -> T,
+{
+    /// `rule` is given a cell and the cells surrounding it, in reading order
+    pub fn new(initial: Matrix<T>, rule: F) -> Automaton<T, F> {
+        let mut automaton = Automaton {
+            next: initial.clone(),
+            current: initial,
+            rule,
+            generation: 0,
+            history: vec![],
+            seen: HashMap::new(),
+            cycle: None,
+        };
+
+        automaton.record();
+        automaton
+    }
+
+    pub fn state(&self) -> &Matrix<T> {
+        &self.current
+    }
+
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// The cycle the automaton has settled in, once a state has repeated
+    pub fn cycle(&self) -> Option<Cycle> {
+        self.cycle
+    }
+
+    pub fn step(&mut self) -> &Matrix<T> {
+        self.run(1)
+    }
+
+    /// Advances `generations` generations. Once a cycle has been found the
+    /// remaining generations are skipped instead of simulated.
+    pub fn run(&mut self, generations: usize) -> &Matrix<T> {
+        let target = self.generation + generations;
+
+        while self.generation < target {
+            if let Some(cycle) = self.cycle {
+                let i = cycle.start + (target - cycle.start) % cycle.length;
+                self.current = self.history[i].clone();
+                self.generation = target;
+                break;
+            }
+
+            self.advance();
+            self.record();
+        }
+
+        &self.current
+    }
+
+    /// Computes the next generation into the back buffer and swaps it in
+    fn advance(&mut self) {
+        let current = &self.current;
+        let mut around = Vec::with_capacity(8);
+
+        for i in 0..current.height {
+            for j in 0..current.width {
+                let s = RowCol::new(i, j);
+                around.clear();
+                around.extend(current.surrounding(s).map(|n| &current[n]));
+                self.next[s] = (self.rule)(&current[s], &around);
+            }
+        }
+
+        mem::swap(&mut self.current, &mut self.next);
+        self.generation += 1;
+    }
+
+    /// Remembers the current state, or notes the cycle if it's been seen
+    fn record(&mut self) {
+        let mut hasher = DefaultHasher::new();
+        self.current.hash(&mut hasher);
+
+        let generations = self.seen.entry(hasher.finish()).or_default();
+        let history = &self.history;
+        let current = &self.current;
+
+        match generations.iter().find(|g| history[**g] == *current) {
+            Some(&start) => {
+                self.cycle = Some(Cycle {
+                    start,
+                    length: self.generation - start,
+                })
+            }
+            None => {
+                generations.push(self.generation);
+                self.history.push(self.current.clone());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::matrix::testing::grid;
+
+    fn life(alive: &bool, around: &[&bool]) -> bool {
+        let n = around.iter().filter(|c| ***c).count();
+        n == 3 || (*alive && n == 2)
+    }
+
+    fn to_cells(s: &str) -> Matrix<bool> {
+        grid(s).map(|c| *c == '#')
+    }
+
+    #[test]
+    fn test_step() {
+        let mut life = Automaton::new(to_cells(".....\n..#..\n..#..\n..#..\n....."), life);
+        assert_eq!(*life.step(), to_cells(".....\n.....\n.###.\n.....\n....."));
+        assert_eq!(life.generation(), 1);
+        assert_eq!(life.cycle(), None);
+    }
+
+    #[test]
+    fn test_blinker_cycle() {
+        let initial = to_cells(".....\n..#..\n..#..\n..#..\n.....");
+        let mut life = Automaton::new(initial.clone(), life);
+
+        life.run(2);
+        assert_eq!(
+            life.cycle(),
+            Some(Cycle {
+                start: 0,
+                length: 2
+            })
+        );
+
+        assert_eq!(*life.run(1_000_000_000 - 2), initial);
+        assert_eq!(life.generation(), 1_000_000_000);
+
+        assert_eq!(*life.step(), to_cells(".....\n.....\n.###.\n.....\n....."));
+    }
+
+    #[test]
+    fn test_cycle_after_transient() {
+        // a lone cell dies, and then nothing ever happens again
+        let mut life = Automaton::new(to_cells("...\n.#.\n..."), life);
+
+        assert_eq!(*life.run(1_000_000), Matrix::new(3, 3, false));
+        assert_eq!(
+            life.cycle(),
+            Some(Cycle {
+                start: 1,
+                length: 1
+            })
+        );
+    }
+}
@@ -0,0 +1,264 @@
+// A boolean matrix packed one bit per cell. Every row starts on a fresh
+// word, so row-wise operations work a whole word at a time and the unused
+// bits at the end of a row are always zero.
+
+use super::{Matrix, RowCol};
+use std::ops::{BitAnd, BitOr, BitXor};
+
+const BITS: usize = 64;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitMatrix {
+    words: Vec<u64>,
+    words_per_row: usize,
+    pub height: usize,
+    pub width: usize,
+}
+
+impl BitMatrix {
+    /// Creates a new matrix with every cell unset
+    pub fn new(height: usize, width: usize) -> BitMatrix {
+        let words_per_row = width.div_ceil(BITS);
+
+        BitMatrix {
+            words: vec![0; height * words_per_row],
+            words_per_row,
+            height,
+            width,
+        }
+    }
+
+    /// word index and bit mask of a cell
+    fn locate(&self, s: RowCol) -> (usize, u64) {
+        assert!(s.row < self.height);
+        assert!(s.col < self.width);
+
+        (
+            s.row * self.words_per_row + s.col / BITS,
+            1 << (s.col % BITS),
+        )
+    }
+
+    pub fn get(&self, s: RowCol) -> bool {
+        let (i, mask) = self.locate(s);
+        self.words[i] & mask != 0
+    }
+
+    pub fn set(&mut self, s: RowCol, value: bool) {
+        let (i, mask) = self.locate(s);
+        if value {
+            self.words[i] |= mask;
+        } else {
+            self.words[i] &= !mask;
+        }
+    }
+
+    /// Flips a cell and returns its new value
+    pub fn toggle(&mut self, s: RowCol) -> bool {
+        let (i, mask) = self.locate(s);
+        self.words[i] ^= mask;
+        self.words[i] & mask != 0
+    }
+
+    /// Number of set cells
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// Number of set cells in one row
+    pub fn count_ones_in_row(&self, row: usize) -> usize {
+        assert!(row < self.height);
+
+        self.row_words(row)
+            .iter()
+            .map(|w| w.count_ones() as usize)
+            .sum()
+    }
+
+    /// Keeps only the cells of `row` that are also set in `other_row` of
+    /// `other`
+    pub fn and_row(&mut self, row: usize, other: &BitMatrix, other_row: usize) {
+        self.combine_row(row, other, other_row, |a, b| a & b);
+    }
+
+    /// Sets the cells of `row` that are set in `other_row` of `other`
+    pub fn or_row(&mut self, row: usize, other: &BitMatrix, other_row: usize) {
+        self.combine_row(row, other, other_row, |a, b| a | b);
+    }
+
+    /// Flips the cells of `row` that are set in `other_row` of `other`
+    pub fn xor_row(&mut self, row: usize, other: &BitMatrix, other_row: usize) {
+        self.combine_row(row, other, other_row, |a, b| a ^ b);
+    }
+
+    pub fn to_matrix(&self) -> Matrix<bool> {
+        Matrix::from_fn(self.height, self.width, |s| self.get(s))
+    }
+
+    fn row_words(&self, row: usize) -> &[u64] {
+        &self.words[row * self.words_per_row..(row + 1) * self.words_per_row]
+    }
+
+    /// Combines a row with a row of another matrix of the same width word by
+    /// word, in place
+    fn combine_row<F>(&mut self, row: usize, other: &BitMatrix, other_row: usize, f: F)
+    where
+        F: Fn(u64, u64) -> u64,
+    {
+        assert!(row < self.height);
+        assert!(other_row < other.height);
+        assert_eq!(self.width, other.width);
+
+        let start = row * self.words_per_row;
+        let words = &mut self.words[start..start + self.words_per_row];
+        for (a, b) in words.iter_mut().zip(other.row_words(other_row)) {
+            *a = f(*a, *b);
+        }
+    }
+
+    /// Combines two matrices of the same size word by word
+    fn combine<F>(&self, other: &BitMatrix, f: F) -> BitMatrix
+    where
+        F: Fn(u64, u64) -> u64,
+    {
+        assert_eq!(self.height, other.height);
+        assert_eq!(self.width, other.width);
+
+        BitMatrix {
+            words: self
+                .words
+                .iter()
+                .zip(other.words.iter())
+                .map(|(a, b)| f(*a, *b))
+                .collect(),
+            ..*self
+        }
+    }
+}
+
+impl From<&Matrix<bool>> for BitMatrix {
+    fn from(m: &Matrix<bool>) -> BitMatrix {
+        let mut bits = BitMatrix::new(m.height, m.width);
+        for i in 0..m.height {
+            for j in 0..m.width {
+                let s = RowCol::new(i, j);
+                bits.set(s, m[s]);
+            }
+        }
+        bits
+    }
+}
+
+impl BitAnd for &BitMatrix {
+    type Output = BitMatrix;
+
+    fn bitand(self, other: &BitMatrix) -> BitMatrix {
+        self.combine(other, |a, b| a & b)
+    }
+}
+
+impl BitOr for &BitMatrix {
+    type Output = BitMatrix;
+
+    fn bitor(self, other: &BitMatrix) -> BitMatrix {
+        self.combine(other, |a, b| a | b)
+    }
+}
+
+impl BitXor for &BitMatrix {
+    type Output = BitMatrix;
+
+    fn bitxor(self, other: &BitMatrix) -> BitMatrix {
+        self.combine(other, |a, b| a ^ b)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_bit_matrix() {
+        let mut m = BitMatrix::new(3, 130);
+        assert_eq!(m.count_ones(), 0);
+
+        m.set(RowCol::new(0, 0), true);
+        m.set(RowCol::new(1, 64), true);
+        m.set(RowCol::new(2, 129), true);
+        assert!(m.get(RowCol::new(0, 0)));
+        assert!(m.get(RowCol::new(1, 64)));
+        assert!(m.get(RowCol::new(2, 129)));
+        assert!(!m.get(RowCol::new(1, 63)));
+        assert_eq!(m.count_ones(), 3);
+        assert_eq!(m.count_ones_in_row(1), 1);
+
+        assert!(!m.toggle(RowCol::new(0, 0)));
+        assert!(m.toggle(RowCol::new(0, 1)));
+        m.set(RowCol::new(1, 64), false);
+        assert_eq!(m.count_ones(), 2);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_bit_matrix_out_of_bounds() {
+        let m = BitMatrix::new(2, 10);
+        m.get(RowCol::new(0, 10));
+    }
+
+    #[test]
+    fn test_bit_matrix_ops() {
+        let a = BitMatrix::from(&Matrix::from_vec(2, 2, vec![true, true, false, false]).unwrap());
+        let b = BitMatrix::from(&Matrix::from_vec(2, 2, vec![true, false, true, false]).unwrap());
+
+        let to_vec = |m: BitMatrix| m.to_matrix().rows().flatten().cloned().collect::<Vec<_>>();
+        assert_eq!(to_vec(&a & &b), vec![true, false, false, false]);
+        assert_eq!(to_vec(&a | &b), vec![true, true, true, false]);
+        assert_eq!(to_vec(&a ^ &b), vec![false, true, true, false]);
+    }
+
+    #[test]
+    fn test_bit_matrix_row_ops() {
+        let mut m = BitMatrix::new(2, 70);
+        let mut other = BitMatrix::new(3, 70);
+        for col in [0, 1, 65, 69] {
+            m.set(RowCol::new(0, col), true);
+        }
+        for col in [1, 2, 65] {
+            other.set(RowCol::new(2, col), true);
+        }
+
+        let row = |m: &BitMatrix, row: usize| {
+            (0..m.width)
+                .filter(|&col| m.get(RowCol::new(row, col)))
+                .collect::<Vec<_>>()
+        };
+
+        let mut and = m.clone();
+        and.and_row(0, &other, 2);
+        assert_eq!(row(&and, 0), vec![1, 65]);
+
+        let mut or = m.clone();
+        or.or_row(0, &other, 2);
+        assert_eq!(row(&or, 0), vec![0, 1, 2, 65, 69]);
+
+        let mut xor = m.clone();
+        xor.xor_row(0, &other, 2);
+        assert_eq!(row(&xor, 0), vec![0, 2, 69]);
+
+        // the other rows are left alone
+        m.or_row(1, &other, 2);
+        assert_eq!(row(&m, 0), vec![0, 1, 65, 69]);
+        assert_eq!(row(&m, 1), vec![1, 2, 65]);
+        assert_eq!(m.count_ones(), 7);
+    }
+
+    #[test]
+    fn test_bit_matrix_round_trip() {
+        let m = Matrix::from_fn(5, 70, |s| (s.row * s.col) % 3 == 0);
+        let bits = BitMatrix::from(&m);
+
+        assert_eq!(bits.to_matrix(), m);
+        assert_eq!(bits.count_ones(), m.count_where(|v| *v));
+        assert_eq!(bits.clone(), bits);
+    }
+}
@@ -0,0 +1,240 @@
+// Applying a kernel at every position of a matrix, and iterating over all
+// the windows of a given size
+
+use super::view::SubMatrix;
+use super::{Matrix, RowCol};
+use std::ops::{Add, Mul, Sub};
+
+/// What a kernel sees when it hangs over the edge of the matrix
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BorderMode {
+    /// Cells outside the matrix read as `T::default()`
+    Zero,
+    /// Cells outside the matrix read as the nearest cell on the edge
+    Clamp,
+    /// The matrix repeats in every direction
+    Wrap,
+    /// Only positions where the kernel fits entirely are computed, so the
+    /// result shrinks by the kernel size minus one in each direction
+    Valid,
+}
+
+/// Number of positions a window of `k` cells fits in along `n` cells
+fn valid_len(n: usize, k: usize) -> usize {
+    if k > n {
+        0
+    } else {
+        n - k + 1
+    }
+}
+
+impl<T> Matrix<T>
+where
+    T: Copy + Default + Add<Output = T> + Mul<Output = T>,
+{
+    /// Multiplies the kernel with the cells under it and sums the products,
+    /// at every position. The kernel is centered on each cell (rounding the
+    /// center up and to the left for even sizes) and isn't flipped. With
+    /// `BorderMode::Valid` the kernel's top left corner is placed on each
+    /// cell of the result instead.
+    pub fn convolve(&self, kernel: &Matrix<T>, border: BorderMode) -> Matrix<T> {
+        let (height, width, anchor) = match border {
+            BorderMode::Valid => (
+                valid_len(self.height, kernel.height),
+                valid_len(self.width, kernel.width),
+                (0, 0),
+            ),
+            _ => (
+                self.height,
+                self.width,
+                ((kernel.height / 2) as isize, (kernel.width / 2) as isize),
+            ),
+        };
+
+        // a matrix with no rows has no columns either
+        let width = if height == 0 { 0 } else { width };
+
+        Matrix::from_fn(height, width, |s| {
+            let mut total = T::default();
+
+            for ki in 0..kernel.height {
+                for kj in 0..kernel.width {
+                    let row = s.row as isize + ki as isize - anchor.0;
+                    let col = s.col as isize + kj as isize - anchor.1;
+
+                    if let Some(p) = self.resolve(row, col, border) {
+                        total = total + kernel[RowCol::new(ki, kj)] * self[p];
+                    }
+                }
+            }
+
+            total
+        })
+    }
+
+    /// The cell a possibly out of range position reads from, if any
+    fn resolve(&self, row: isize, col: isize, border: BorderMode) -> Option<RowCol> {
+        let (h, w) = (self.height as isize, self.width as isize);
+
+        match border {
+            BorderMode::Zero | BorderMode::Valid => {
+                if row < 0 || col < 0 || row >= h || col >= w {
+                    None
+                } else {
+                    Some(RowCol::new(row as usize, col as usize))
+                }
+            }
+            BorderMode::Clamp => Some(RowCol::new(
+                row.clamp(0, h - 1) as usize,
+                col.clamp(0, w - 1) as usize,
+            )),
+            BorderMode::Wrap => Some(RowCol::new(
+                row.rem_euclid(h) as usize,
+                col.rem_euclid(w) as usize,
+            )),
+        }
+    }
+}
+
+impl<T> Matrix<T> {
+    /// Every `height` x `width` window that fits in the matrix, ordered by
+    /// the reading order of their top left corners
+    pub fn sliding_window(
+        &self,
+        height: usize,
+        width: usize,
+    ) -> impl Iterator<Item = SubMatrix<'_, T>> {
+        let rows = valid_len(self.height, height);
+        let cols = valid_len(self.width, width);
+
+        (0..rows)
+            .flat_map(move |i| (0..cols).map(move |j| self.view(RowCol::new(i, j), height, width)))
+    }
+}
+
+impl<T> Matrix<T>
+where
+    T: Copy + Default + Add<Output = T> + Sub<Output = T>,
+{
+    /// The sum of every `height` x `width` window, stored at the window's top
+    /// left corner. Uses a summed-area table, so the cost doesn't depend on
+    /// the window size.
+    pub fn window_sums(&self, height: usize, width: usize) -> Matrix<T> {
+        // sums[i][j] is the sum of every cell above and to the left of (i, j)
+        let mut sums = Matrix::new(self.height + 1, self.width + 1, T::default());
+        for i in 0..self.height {
+            for j in 0..self.width {
+                sums[RowCol::new(i + 1, j + 1)] = self[RowCol::new(i, j)]
+                    + sums[RowCol::new(i, j + 1)]
+                    + sums[RowCol::new(i + 1, j)]
+                    - sums[RowCol::new(i, j)];
+            }
+        }
+
+        let rows = valid_len(self.height, height);
+        let cols = if rows == 0 {
+            0
+        } else {
+            valid_len(self.width, width)
+        };
+
+        Matrix::from_fn(rows, cols, |s| {
+            let (top, left) = (s.row, s.col);
+            let (bottom, right) = (s.row + height, s.col + width);
+
+            // adding before subtracting keeps unsigned sums from underflowing
+            sums[RowCol::new(bottom, right)] + sums[RowCol::new(top, left)]
+                - sums[RowCol::new(top, right)]
+                - sums[RowCol::new(bottom, left)]
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn grid() -> Matrix<i32> {
+        Matrix::from_vec(3, 3, vec![1, 2, 3, 4, 5, 6, 7, 8, 9]).unwrap()
+    }
+
+    #[test]
+    fn test_convolve_zero() {
+        let ones = Matrix::new(3, 3, 1);
+        let m = grid().convolve(&ones, BorderMode::Zero);
+
+        assert_eq!(
+            m,
+            Matrix::from_vec(3, 3, vec![12, 21, 16, 27, 45, 33, 24, 39, 28]).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_convolve_clamp_and_wrap() {
+        let ones = Matrix::new(3, 3, 1);
+
+        let m = grid().convolve(&ones, BorderMode::Clamp);
+        assert_eq!(m[RowCol::new(0, 0)], 1 + 1 + 2 + 1 + 1 + 2 + 4 + 4 + 5);
+        assert_eq!(m[RowCol::new(1, 1)], 45);
+
+        // every window covers each row and column exactly once
+        let m = grid().convolve(&ones, BorderMode::Wrap);
+        assert_eq!(m, Matrix::new(3, 3, 45));
+    }
+
+    #[test]
+    fn test_convolve_valid() {
+        let kernel = Matrix::from_vec(1, 2, vec![1, -1]).unwrap();
+        let m = grid().convolve(&kernel, BorderMode::Valid);
+
+        assert_eq!(m, Matrix::new(3, 2, -1));
+
+        let too_big = Matrix::new(4, 1, 1);
+        let m = grid().convolve(&too_big, BorderMode::Valid);
+        assert_eq!((m.height, m.width), (0, 0));
+    }
+
+    #[test]
+    fn test_convolve_is_not_flipped() {
+        // picks out the cell to the right of each position
+        let kernel = Matrix::from_vec(1, 3, vec![0, 0, 1]).unwrap();
+        let m = grid().convolve(&kernel, BorderMode::Zero);
+
+        assert_eq!(
+            m,
+            Matrix::from_vec(3, 3, vec![2, 3, 0, 5, 6, 0, 8, 9, 0]).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_sliding_window() {
+        let m = grid();
+        let windows: Vec<Matrix<i32>> = m.sliding_window(2, 2).map(|w| w.to_matrix()).collect();
+
+        assert_eq!(windows.len(), 4);
+        assert_eq!(
+            windows[0],
+            Matrix::from_vec(2, 2, vec![1, 2, 4, 5]).unwrap()
+        );
+        assert_eq!(
+            windows[3],
+            Matrix::from_vec(2, 2, vec![5, 6, 8, 9]).unwrap()
+        );
+
+        assert_eq!(m.sliding_window(4, 1).count(), 0);
+    }
+
+    #[test]
+    fn test_window_sums() {
+        let m = Matrix::from_fn(6, 5, |s| (s.row * 7 + s.col * 3) as u32 % 5);
+        let sums = m.window_sums(3, 2);
+
+        assert_eq!((sums.height, sums.width), (4, 4));
+
+        let expected: Vec<u32> = m
+            .sliding_window(3, 2)
+            .map(|w| w.to_matrix().sum())
+            .collect();
+        assert_eq!(sums, Matrix::from_vec(4, 4, expected).unwrap());
+    }
+}
@@ -0,0 +1,74 @@
+// Typed positions in a matrix. A matrix is stored row by row, but puzzles
+// usually talk about x (across) and y (down); keeping the two in separate
+// types means a swapped pair is a compile error rather than a wrong answer.
+// Both are unsigned by default, and signed (`RowCol<isize>`, `Pos<isize>`)
+// for the grids that can grow in every direction.
+
+use serde_derive::{Deserialize, Serialize};
+
+/// A position as (row, col). Orders in reading order: top to bottom, then
+/// left to right.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default, Serialize, Deserialize,
+)]
+pub struct RowCol<I = usize> {
+    pub row: I,
+    pub col: I,
+}
+
+/// A position as (x, y), with x growing to the right and y growing down
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub struct Pos<I = usize> {
+    pub x: I,
+    pub y: I,
+}
+
+impl<I> RowCol<I> {
+    pub fn new(row: I, col: I) -> RowCol<I> {
+        RowCol { row, col }
+    }
+}
+
+impl<I> Pos<I> {
+    pub fn new(x: I, y: I) -> Pos<I> {
+        Pos { x, y }
+    }
+}
+
+impl<I> From<Pos<I>> for RowCol<I> {
+    fn from(p: Pos<I>) -> RowCol<I> {
+        RowCol { row: p.y, col: p.x }
+    }
+}
+
+impl<I> From<RowCol<I>> for Pos<I> {
+    fn from(s: RowCol<I>) -> Pos<I> {
+        Pos { x: s.col, y: s.row }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_conversions() {
+        let p = Pos::new(3, 5);
+        let s = RowCol::from(p);
+        assert_eq!(s, RowCol::new(5, 3));
+        assert_eq!(Pos::from(s), p);
+
+        let p: Pos<isize> = Pos::new(-3, 5);
+        assert_eq!(RowCol::from(p), RowCol::new(5, -3));
+    }
+
+    #[test]
+    fn test_reading_order() {
+        let mut positions = vec![RowCol::new(1, 0), RowCol::new(0, 5), RowCol::new(0, 2)];
+        positions.sort();
+        assert_eq!(
+            positions,
+            vec![RowCol::new(0, 2), RowCol::new(0, 5), RowCol::new(1, 0)]
+        );
+    }
+}
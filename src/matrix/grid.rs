@@ -0,0 +1,153 @@
+// A common interface over the different grid representations so that
+// algorithms and renderers don't care how the cells are stored
+
+use super::{Bounds, Matrix, OffsetMatrix, RowCol};
+
+pub type Cells<'a, T> = Box<dyn Iterator<Item = (RowCol<isize>, &'a T)> + 'a>;
+
+pub trait Grid<T> {
+    /// Returns the value at a position, if there is one
+    fn get(&self, pos: RowCol<isize>) -> Option<&T>;
+
+    /// Stores a value at a position
+    fn set(&mut self, pos: RowCol<isize>, value: T);
+
+    /// The bounding box of all the positions holding a value
+    fn bounds(&self) -> Option<Bounds>;
+
+    /// Iterates over every position holding a value
+    fn cells(&self) -> Cells<'_, T>;
+
+    /// Draws the bounding box one character per cell, one line per row
+    fn render<F>(&self, to_char: F) -> String
+    where
+        F: Fn(Option<&T>) -> char,
+    {
+        let bounds = match self.bounds() {
+            Some(bounds) => bounds,
+            None => return String::new(),
+        };
+
+        (bounds.min.row..=bounds.max.row)
+            .map(|row| {
+                (bounds.min.col..=bounds.max.col)
+                    .map(|col| to_char(self.get(RowCol::new(row, col))))
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl<T> Grid<T> for Matrix<T> {
+    fn get(&self, pos: RowCol<isize>) -> Option<&T> {
+        if pos.row < 0
+            || pos.col < 0
+            || pos.row as usize >= self.height
+            || pos.col as usize >= self.width
+        {
+            None
+        } else {
+            Some(&self[RowCol::new(pos.row as usize, pos.col as usize)])
+        }
+    }
+
+    /// Panics if the position is outside the matrix
+    fn set(&mut self, pos: RowCol<isize>, value: T) {
+        assert!(pos.row >= 0 && pos.col >= 0);
+        self[RowCol::new(pos.row as usize, pos.col as usize)] = value;
+    }
+
+    fn bounds(&self) -> Option<Bounds> {
+        if self.height == 0 || self.width == 0 {
+            None
+        } else {
+            Some(Bounds {
+                min: RowCol::new(0, 0),
+                max: RowCol::new(self.height as isize - 1, self.width as isize - 1),
+            })
+        }
+    }
+
+    fn cells(&self) -> Cells<'_, T> {
+        let width = self.width;
+
+        Box::new(
+            self.buf
+                .iter()
+                .enumerate()
+                .map(move |(i, v)| (RowCol::new((i / width) as isize, (i % width) as isize), v)),
+        )
+    }
+}
+
+impl<T> Grid<T> for OffsetMatrix<T>
+where
+    T: Copy,
+{
+    /// Positions inside the occupied bounding box always have a value
+    fn get(&self, pos: RowCol<isize>) -> Option<&T> {
+        match self.bounds() {
+            Some(bounds) if bounds.contains(pos) => Some(&self[pos]),
+            _ => None,
+        }
+    }
+
+    fn set(&mut self, pos: RowCol<isize>, value: T) {
+        self[pos] = value;
+    }
+
+    fn bounds(&self) -> Option<Bounds> {
+        OffsetMatrix::bounds(self)
+    }
+
+    fn cells(&self) -> Cells<'_, T> {
+        Box::new(self.bounds().into_iter().flat_map(move |b| {
+            (b.min.row..=b.max.row)
+                .flat_map(move |row| (b.min.col..=b.max.col).map(move |col| RowCol::new(row, col)))
+                .map(move |pos| (pos, &self[pos]))
+        }))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// counts the '#' cells without caring about the representation
+    fn count_walls<G: Grid<char>>(grid: &G) -> usize {
+        grid.cells().filter(|(_, c)| **c == '#').count()
+    }
+
+    #[test]
+    fn test_grid_matrix() {
+        let mut m = Matrix::new(2, 3, '.');
+        m.set(RowCol::new(0, 1), '#');
+        m.set(RowCol::new(1, 2), '#');
+
+        assert_eq!(m.get(RowCol::new(0, 1)), Some(&'#'));
+        assert_eq!(m.get(RowCol::new(-1, 0)), None);
+        assert_eq!(m.get(RowCol::new(2, 0)), None);
+        assert_eq!(
+            Grid::bounds(&m),
+            Some(Bounds {
+                min: RowCol::new(0, 0),
+                max: RowCol::new(1, 2)
+            })
+        );
+        assert_eq!(count_walls(&m), 2);
+        assert_eq!(m.render(|c| *c.unwrap()), ".#.\n..#");
+    }
+
+    #[test]
+    fn test_grid_offset_matrix() {
+        let mut m = OffsetMatrix::new('.');
+        m.set(RowCol::new(-1, -1), '#');
+        m.set(RowCol::new(0, 1), '#');
+
+        assert_eq!(Grid::get(&m, RowCol::new(0, 0)), Some(&'.'));
+        assert_eq!(Grid::get(&m, RowCol::new(5, 5)), None);
+        assert_eq!(count_walls(&m), 2);
+        assert_eq!(m.render(|c| *c.unwrap()), "#..\n..#");
+    }
+}
@@ -0,0 +1,214 @@
+// An N-dimensional counterpart of Matrix, again backed by a single flat
+// vector. Positions are arrays of D indices, with the last index varying
+// fastest, so for D = 2 the layout and ordering match Matrix's reading order.
+
+use std::ops::{Index, IndexMut};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GridN<T, const D: usize> {
+    buf: Vec<T>,
+    dims: [usize; D],
+}
+
+pub type Grid3<T> = GridN<T, 3>;
+
+impl<T, const D: usize> GridN<T, D>
+where
+    T: Clone,
+{
+    /// Creates a new grid with the given size along each axis
+    pub fn new(dims: [usize; D], initial: T) -> GridN<T, D> {
+        GridN {
+            buf: vec![initial; dims.iter().product()],
+            dims,
+        }
+    }
+}
+
+impl<T, const D: usize> GridN<T, D> {
+    /// Creates a new grid where every cell is computed from its position
+    pub fn from_fn<F>(dims: [usize; D], mut f: F) -> GridN<T, D>
+    where
+        F: FnMut([usize; D]) -> T,
+    {
+        let len = dims.iter().product();
+        GridN {
+            buf: (0..len).map(|i| f(position_of(&dims, i))).collect(),
+            dims,
+        }
+    }
+
+    /// The size along each axis
+    pub fn dims(&self) -> [usize; D] {
+        self.dims
+    }
+
+    pub fn len(&self) -> usize {
+        self.buf.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buf.is_empty()
+    }
+
+    /// Whether a position lies inside the grid
+    pub fn contains(&self, pos: [usize; D]) -> bool {
+        pos.iter().zip(self.dims.iter()).all(|(p, d)| p < d)
+    }
+
+    fn index_of(&self, pos: [usize; D]) -> usize {
+        assert!(self.contains(pos));
+
+        pos.iter()
+            .zip(self.dims.iter())
+            .fold(0, |acc, (p, d)| acc * d + p)
+    }
+
+    /// The neighbors of a position one step along a single axis, in order
+    pub fn neighbors(&self, pos: [usize; D]) -> impl Iterator<Item = [usize; D]> {
+        let mut positions = vec![];
+        for axis in 0..D {
+            for delta in &[-1, 1] {
+                let mut offset = [0; D];
+                offset[axis] = *delta;
+                positions.extend(self.step(pos, offset));
+            }
+        }
+
+        positions.sort();
+        positions.into_iter()
+    }
+
+    /// Every position that differs by at most one along each axis (the
+    /// 3^D - 1 cells around it, diagonals included), in order
+    pub fn surrounding(&self, pos: [usize; D]) -> impl Iterator<Item = [usize; D]> {
+        let mut positions = vec![];
+
+        // count through {-1, 0, 1}^D in base 3, which keeps the order sorted
+        for n in 0..3usize.pow(D as u32) {
+            let mut offset = [0; D];
+            let mut rest = n;
+            for axis in (0..D).rev() {
+                offset[axis] = (rest % 3) as isize - 1;
+                rest /= 3;
+            }
+
+            if offset.iter().any(|o| *o != 0) {
+                positions.extend(self.step(pos, offset));
+            }
+        }
+
+        positions.into_iter()
+    }
+
+    /// Moves a position by an offset, if the result is still in the grid
+    fn step(&self, pos: [usize; D], offset: [isize; D]) -> Option<[usize; D]> {
+        let mut next = pos;
+        for axis in 0..D {
+            let p = pos[axis] as isize + offset[axis];
+            if p < 0 || p as usize >= self.dims[axis] {
+                return None;
+            }
+            next[axis] = p as usize;
+        }
+
+        Some(next)
+    }
+
+    /// Iterates over every position and its value, in order
+    pub fn cells(&self) -> impl Iterator<Item = ([usize; D], &T)> {
+        let dims = self.dims;
+        self.buf
+            .iter()
+            .enumerate()
+            .map(move |(i, v)| (position_of(&dims, i), v))
+    }
+}
+
+/// Inverse of `index_of`: the position stored at index `i` of the buffer
+fn position_of<const D: usize>(dims: &[usize; D], mut i: usize) -> [usize; D] {
+    let mut pos = [0; D];
+    for axis in (0..D).rev() {
+        pos[axis] = i % dims[axis];
+        i /= dims[axis];
+    }
+    pos
+}
+
+impl<T, const D: usize> Index<[usize; D]> for GridN<T, D> {
+    type Output = T;
+
+    fn index(&self, pos: [usize; D]) -> &T {
+        let i = self.index_of(pos);
+        &self.buf[i]
+    }
+}
+
+impl<T, const D: usize> IndexMut<[usize; D]> for GridN<T, D> {
+    fn index_mut(&mut self, pos: [usize; D]) -> &mut T {
+        let i = self.index_of(pos);
+        &mut self.buf[i]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_grid3() {
+        let mut g: Grid3<i32> = GridN::new([2, 3, 4], 0);
+        assert_eq!(g.len(), 24);
+
+        g[[1, 2, 3]] = 5;
+        g[[0, 1, 0]] = 7;
+        assert_eq!(g[[1, 2, 3]], 5);
+        assert_eq!(g[[0, 1, 0]], 7);
+        assert_eq!(g[[1, 1, 0]], 0);
+
+        assert!(g.contains([1, 2, 3]));
+        assert!(!g.contains([2, 0, 0]));
+
+        let total: i32 = g.cells().map(|(_, v)| v).sum();
+        assert_eq!(total, 12);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_grid3_out_of_bounds() {
+        let g = GridN::new([2, 2, 2], 0);
+        let _ = g[[0, 2, 0]];
+    }
+
+    #[test]
+    fn test_gridn_from_fn() {
+        let g = GridN::from_fn([2, 2, 2, 2], |p| p.iter().sum::<usize>());
+        assert_eq!(g[[1, 0, 1, 1]], 3);
+
+        let positions: Vec<_> = g.cells().map(|(p, _)| p).take(3).collect();
+        assert_eq!(positions, vec![[0, 0, 0, 0], [0, 0, 0, 1], [0, 0, 1, 0]]);
+    }
+
+    #[test]
+    fn test_gridn_neighbors() {
+        let g = GridN::new([3, 3, 3], 0);
+
+        let corner: Vec<_> = g.neighbors([0, 0, 0]).collect();
+        assert_eq!(corner, vec![[0, 0, 1], [0, 1, 0], [1, 0, 0]]);
+
+        assert_eq!(g.neighbors([1, 1, 1]).count(), 6);
+        assert_eq!(g.surrounding([1, 1, 1]).count(), 26);
+        assert_eq!(g.surrounding([0, 0, 0]).count(), 7);
+
+        let around: Vec<_> = g.surrounding([1, 1, 1]).collect();
+        let mut sorted = around.clone();
+        sorted.sort();
+        assert_eq!(around, sorted);
+        assert!(!around.contains(&[1, 1, 1]));
+
+        // in two dimensions the order matches Matrix's reading order
+        let flat = GridN::new([3, 4], 0);
+        let middle: Vec<_> = flat.neighbors([1, 2]).collect();
+        assert_eq!(middle, vec![[0, 2], [1, 1], [1, 3], [2, 2]]);
+    }
+}
@@ -0,0 +1,171 @@
+// Exporting a matrix as a binary PPM (color) or PGM (grayscale) image, one
+// pixel per cell, so grids can be looked at in any image viewer.
+
+use super::Matrix;
+use std::io;
+use std::io::prelude::*;
+
+pub type Rgb = (u8, u8, u8);
+
+impl<T> Matrix<T> {
+    /// Writes a color image, with `palette` picking the color of each cell
+    pub fn write_ppm<W, F>(&self, out: &mut W, palette: F) -> io::Result<()>
+    where
+        W: Write,
+        F: Fn(&T) -> Rgb,
+    {
+        write!(out, "P6\n{} {}\n255\n", self.width, self.height)?;
+
+        let pixels: Vec<u8> = self
+            .buf
+            .iter()
+            .map(palette)
+            .flat_map(|(r, g, b)| vec![r, g, b])
+            .collect();
+
+        out.write_all(&pixels)
+    }
+
+    /// Writes a grayscale image, with `shade` picking the brightness of each cell
+    pub fn write_pgm<W, F>(&self, out: &mut W, shade: F) -> io::Result<()>
+    where
+        W: Write,
+        F: Fn(&T) -> u8,
+    {
+        write!(out, "P5\n{} {}\n255\n", self.width, self.height)?;
+
+        let pixels: Vec<u8> = self.buf.iter().map(shade).collect();
+        out.write_all(&pixels)
+    }
+}
+
+impl<T> Matrix<T> {
+    /// Scales every cell's `value` into 0.0..=1.0, where 0.0 is the smallest
+    /// value in the matrix and 1.0 the largest
+    pub fn normalized<F>(&self, value: F) -> Matrix<f64>
+    where
+        F: Fn(&T) -> f64,
+    {
+        let values = self.map(value);
+        let min = values.buf.iter().cloned().fold(f64::INFINITY, f64::min);
+        let max = values.buf.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+
+        if max > min {
+            values.map(|v| (v - min) / (max - min))
+        } else {
+            values.map(|_| 0.0)
+        }
+    }
+
+    /// Writes the normalized values using the `heat` palette
+    pub fn write_heatmap<W, F>(&self, out: &mut W, value: F) -> io::Result<()>
+    where
+        W: Write,
+        F: Fn(&T) -> f64,
+    {
+        self.normalized(value).write_ppm(out, |v| heat(*v))
+    }
+}
+
+/// Black for 0.0 through to white for 1.0
+pub fn grayscale(v: f64) -> Rgb {
+    let c = to_byte(v);
+    (c, c, c)
+}
+
+/// Black through red and yellow to white as `v` goes from 0.0 to 1.0
+pub fn heat(v: f64) -> Rgb {
+    let v = v.clamp(0.0, 1.0) * 3.0;
+    (to_byte(v), to_byte(v - 1.0), to_byte(v - 2.0))
+}
+
+/// A distinct color for each small integer, handy for region ids.
+/// Colors are spread around the hue circle by the golden ratio.
+pub fn categorical(i: usize) -> Rgb {
+    let hue = (i as f64 * 0.618_033_988_75).fract() * 6.0;
+    let x = 1.0 - (hue % 2.0 - 1.0).abs();
+
+    let (r, g, b) = match hue as usize {
+        0 => (1.0, x, 0.0),
+        1 => (x, 1.0, 0.0),
+        2 => (0.0, 1.0, x),
+        3 => (0.0, x, 1.0),
+        4 => (x, 0.0, 1.0),
+        _ => (1.0, 0.0, x),
+    };
+
+    (to_byte(r), to_byte(g), to_byte(b))
+}
+
+fn to_byte(v: f64) -> u8 {
+    (v.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_ppm() {
+        let m = Matrix::from_vec(1, 2, vec!['#', '.']).unwrap();
+        let mut out = vec![];
+        m.write_ppm(
+            &mut out,
+            |c| if *c == '#' { (255, 0, 0) } else { (0, 0, 0) },
+        )
+        .unwrap();
+
+        let mut expected = b"P6\n2 1\n255\n".to_vec();
+        expected.extend_from_slice(&[255, 0, 0, 0, 0, 0]);
+        assert_eq!(out, expected);
+    }
+
+    #[test]
+    fn test_pgm() {
+        let m = Matrix::from_vec(2, 2, vec![0u8, 64, 128, 255]).unwrap();
+        let mut out = vec![];
+        m.write_pgm(&mut out, |v| *v).unwrap();
+
+        let mut expected = b"P5\n2 2\n255\n".to_vec();
+        expected.extend_from_slice(&[0, 64, 128, 255]);
+        assert_eq!(out, expected);
+    }
+
+    #[test]
+    fn test_normalized() {
+        let m = Matrix::from_vec(1, 3, vec![-5, 0, 15]).unwrap();
+        assert_eq!(
+            m.normalized(|v| f64::from(*v)),
+            Matrix::from_vec(1, 3, vec![0.0, 0.25, 1.0]).unwrap()
+        );
+
+        // integer types without an f64 conversion work too
+        let m = Matrix::from_vec(1, 3, vec![10usize, 30, 50]).unwrap();
+        assert_eq!(
+            m.normalized(|v| *v as f64),
+            Matrix::from_vec(1, 3, vec![0.0, 0.5, 1.0]).unwrap()
+        );
+
+        let flat = Matrix::new(2, 2, 7);
+        assert_eq!(flat.normalized(|v| f64::from(*v)), Matrix::new(2, 2, 0.0));
+    }
+
+    #[test]
+    fn test_heatmap() {
+        let m = Matrix::from_vec(1, 2, vec![1i64, 3]).unwrap();
+        let mut out = vec![];
+        m.write_heatmap(&mut out, |v| *v as f64).unwrap();
+        assert_eq!(&out[out.len() - 6..], &[0, 0, 0, 255, 255, 255]);
+    }
+
+    #[test]
+    fn test_palettes() {
+        assert_eq!(grayscale(0.5), (128, 128, 128));
+        assert_eq!(heat(0.0), (0, 0, 0));
+        assert_eq!(heat(0.5), (255, 128, 0));
+        assert_eq!(heat(2.0), (255, 255, 255));
+
+        assert_eq!(categorical(0), (255, 0, 0));
+        assert!(categorical(1) != categorical(2));
+    }
+}
@@ -0,0 +1,233 @@
+// A matrix that is addressed with signed positions and grows its backing
+// buffer whenever it's written to outside its current bounds
+
+use super::{Bounds, Matrix, RowCol};
+use std::ops::{Index, IndexMut};
+
+pub struct OffsetMatrix<T> {
+    cells: Matrix<T>,
+    origin: RowCol<isize>, // position of the top left cell
+    occupied: Option<Bounds>,
+    default: T,
+}
+
+impl<T> OffsetMatrix<T>
+where
+    T: Copy,
+{
+    /// Creates an empty matrix where every position reads as `default`
+    pub fn new(default: T) -> OffsetMatrix<T> {
+        OffsetMatrix {
+            cells: Matrix::new(0, 0, default),
+            origin: RowCol::new(0, 0),
+            occupied: None,
+            default,
+        }
+    }
+
+    /// Sets the value at a position, growing the matrix if needed
+    pub fn set<S: Into<RowCol<isize>>>(&mut self, pos: S, value: T) {
+        self[pos.into()] = value;
+    }
+
+    /// Returns the rows of the occupied bounding box, top to bottom
+    pub fn rows(&self) -> impl Iterator<Item = Vec<&T>> {
+        let bounds = self.occupied;
+
+        bounds
+            .into_iter()
+            .flat_map(|b| b.min.row..=b.max.row)
+            .map(move |row| {
+                let b = bounds.unwrap();
+                (b.min.col..=b.max.col)
+                    .map(|col| &self[RowCol::new(row, col)])
+                    .collect()
+            })
+    }
+
+    /// Copies the occupied bounding box into a dense matrix
+    pub fn to_matrix(&self) -> Matrix<T> {
+        let bounds = match self.occupied {
+            Some(bounds) => bounds,
+            None => return Matrix::new(0, 0, self.default),
+        };
+
+        let mut m = Matrix::new(bounds.height(), bounds.width(), self.default);
+        for i in 0..m.height {
+            for j in 0..m.width {
+                let pos = RowCol::new(bounds.min.row + i as isize, bounds.min.col + j as isize);
+                m[RowCol::new(i, j)] = self[pos];
+            }
+        }
+
+        m
+    }
+
+    fn grow_to(&mut self, pos: RowCol<isize>) {
+        let (row_start, height) = grown_range(self.origin.row, self.cells.height, pos.row);
+        let (col_start, width) = grown_range(self.origin.col, self.cells.width, pos.col);
+
+        if height == self.cells.height && width == self.cells.width {
+            return;
+        }
+
+        let mut cells = Matrix::new(height, width, self.default);
+        let di = (self.origin.row - row_start) as usize;
+        let dj = (self.origin.col - col_start) as usize;
+
+        for i in 0..self.cells.height {
+            for j in 0..self.cells.width {
+                cells[RowCol::new(i + di, j + dj)] = self.cells[RowCol::new(i, j)];
+            }
+        }
+
+        self.cells = cells;
+        self.origin = RowCol::new(row_start, col_start);
+    }
+}
+
+impl<T> OffsetMatrix<T> {
+    /// The bounding box of every position that has been written to
+    pub fn bounds(&self) -> Option<Bounds> {
+        self.occupied
+    }
+
+    fn local(&self, pos: RowCol<isize>) -> Option<RowCol> {
+        let i = pos.row - self.origin.row;
+        let j = pos.col - self.origin.col;
+
+        if i < 0 || j < 0 || i as usize >= self.cells.height || j as usize >= self.cells.width {
+            None
+        } else {
+            Some(RowCol::new(i as usize, j as usize))
+        }
+    }
+}
+
+/// Returns a range (as start and length) that covers both `start..start + len`
+/// and `x`. The length at least doubles when it grows so that writing cell by
+/// cell in one direction doesn't copy the whole buffer every time.
+fn grown_range(start: isize, len: usize, x: isize) -> (isize, usize) {
+    let end = start + len as isize;
+
+    if len == 0 {
+        (x, 1)
+    } else if x < start {
+        let len = (2 * len).max((end - x) as usize);
+        (end - len as isize, len)
+    } else if x >= end {
+        let len = (2 * len).max((x - start + 1) as usize);
+        (start, len)
+    } else {
+        (start, len)
+    }
+}
+
+/// Indexed by a signed `RowCol` or `Pos`, like `Matrix` is by unsigned ones
+impl<T, S> Index<S> for OffsetMatrix<T>
+where
+    S: Into<RowCol<isize>>,
+{
+    type Output = T;
+
+    /// Positions that have never been written to read as the default value
+    fn index(&self, pos: S) -> &T {
+        match self.local(pos.into()) {
+            Some(s) => &self.cells[s],
+            None => &self.default,
+        }
+    }
+}
+
+impl<T, S> IndexMut<S> for OffsetMatrix<T>
+where
+    T: Copy,
+    S: Into<RowCol<isize>>,
+{
+    fn index_mut(&mut self, pos: S) -> &mut T {
+        let pos = pos.into();
+        self.grow_to(pos);
+
+        match self.occupied.as_mut() {
+            Some(bounds) => bounds.include(pos),
+            None => self.occupied = Some(Bounds::at(pos)),
+        }
+
+        let s = self.local(pos).unwrap();
+        &mut self.cells[s]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::matrix::Pos;
+
+    #[test]
+    fn test_offset_matrix() {
+        let mut m = OffsetMatrix::new(0);
+        assert_eq!(m.bounds(), None);
+        assert_eq!(m[RowCol::new(-5, 7)], 0);
+
+        m[RowCol::new(-2, 3)] = 1;
+        m[RowCol::new(4, -1)] += 2;
+        m.set(RowCol::new(0, 0), 3);
+
+        assert_eq!(m[RowCol::new(-2, 3)], 1);
+        assert_eq!(m[RowCol::new(4, -1)], 2);
+        assert_eq!(m[RowCol::new(0, 0)], 3);
+        assert_eq!(m[RowCol::new(100, -100)], 0);
+
+        assert_eq!(
+            m.bounds(),
+            Some(Bounds {
+                min: RowCol::new(-2, -1),
+                max: RowCol::new(4, 3)
+            })
+        );
+    }
+
+    #[test]
+    fn test_offset_matrix_grows_both_ways() {
+        let mut m = OffsetMatrix::new(0);
+        for i in -50..50 {
+            m[RowCol::new(i, -i)] = i;
+        }
+
+        for i in -50..50 {
+            assert_eq!(m[RowCol::new(i, -i)], i);
+        }
+        assert_eq!(m[RowCol::new(1, 1)], 0);
+    }
+
+    #[test]
+    fn test_offset_matrix_pos() {
+        let mut m = OffsetMatrix::new(0);
+        m[Pos::new(-3, 7)] = 1;
+        m.set(Pos::new(2, -4), 2);
+
+        // x is the column and y the row
+        assert_eq!(m[RowCol::new(7, -3)], 1);
+        assert_eq!(m[RowCol::new(-4, 2)], 2);
+        assert_eq!(m[Pos::new(7, -3)], 0);
+    }
+
+    #[test]
+    fn test_offset_matrix_rows() {
+        let mut m = OffsetMatrix::new('.');
+        m[RowCol::new(-1, -1)] = '#';
+        m[RowCol::new(0, 1)] = '#';
+
+        let rows: Vec<String> = m
+            .rows()
+            .map(|row| row.into_iter().collect::<String>())
+            .collect();
+        assert_eq!(rows, vec!["#..", "..#"]);
+
+        assert_eq!(
+            format!("{:?}", m.to_matrix()),
+            "[['#', '.', '.']
+ ['.', '.', '#']]"
+        );
+    }
+}
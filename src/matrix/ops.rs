@@ -0,0 +1,203 @@
+// Whole-matrix operations: mapping, combining two matrices cell by cell,
+// arithmetic and reductions
+
+use super::{Matrix, RowCol};
+use std::iter::Sum;
+use std::ops::{Add, Mul, Sub};
+
+impl<T> Matrix<T> {
+    /// Applies `f` to every cell
+    pub fn map<U, F>(&self, f: F) -> Matrix<U>
+    where
+        F: Fn(&T) -> U,
+    {
+        Matrix {
+            buf: self.buf.iter().map(f).collect(),
+            height: self.height,
+            width: self.width,
+        }
+    }
+
+    /// Combines two matrices of the same size cell by cell
+    pub fn zip_with<U, V, F>(&self, other: &Matrix<U>, f: F) -> Matrix<V>
+    where
+        F: Fn(&T, &U) -> V,
+    {
+        assert_eq!(self.height, other.height);
+        assert_eq!(self.width, other.width);
+
+        Matrix {
+            buf: self
+                .buf
+                .iter()
+                .zip(other.buf.iter())
+                .map(|(a, b)| f(a, b))
+                .collect(),
+            height: self.height,
+            width: self.width,
+        }
+    }
+
+    /// Number of cells for which `predicate` holds
+    pub fn count_where<F>(&self, predicate: F) -> usize
+    where
+        F: Fn(&T) -> bool,
+    {
+        self.buf.iter().filter(|v| predicate(v)).count()
+    }
+
+    /// The position and value of the cell with the largest key. Of several
+    /// equally large cells, the first in reading order is returned.
+    pub fn max_by_key<K, F>(&self, f: F) -> Option<(RowCol, &T)>
+    where
+        K: Ord,
+        F: Fn(&T) -> K,
+    {
+        let mut best: Option<(usize, K)> = None;
+
+        for (i, v) in self.buf.iter().enumerate() {
+            let k = f(v);
+            let larger = match &best {
+                Some((_, best_k)) => k > *best_k,
+                None => true,
+            };
+            if larger {
+                best = Some((i, k));
+            }
+        }
+
+        best.map(|(i, _)| (RowCol::new(i / self.width, i % self.width), &self.buf[i]))
+    }
+}
+
+impl<T> Matrix<T>
+where
+    T: Clone,
+{
+    /// Sets every cell to `value`
+    pub fn fill(&mut self, value: T) {
+        for v in self.buf.iter_mut() {
+            *v = value.clone();
+        }
+    }
+}
+
+impl<T> Matrix<T>
+where
+    T: Copy + Sum,
+{
+    pub fn sum(&self) -> T {
+        self.buf.iter().cloned().sum()
+    }
+}
+
+// Implements an arithmetic operator both cell by cell between two matrices
+// and between a matrix and a scalar, for borrowed and owned matrices
+macro_rules! impl_op {
+    ($trait:ident, $method:ident) => {
+        impl<'a, T> $trait<&'a Matrix<T>> for &'a Matrix<T>
+        where
+            T: $trait<Output = T> + Copy,
+        {
+            type Output = Matrix<T>;
+
+            fn $method(self, other: &Matrix<T>) -> Matrix<T> {
+                self.zip_with(other, |a, b| a.$method(*b))
+            }
+        }
+
+        impl<T> $trait<Matrix<T>> for Matrix<T>
+        where
+            T: $trait<Output = T> + Copy,
+        {
+            type Output = Matrix<T>;
+
+            fn $method(self, other: Matrix<T>) -> Matrix<T> {
+                (&self).$method(&other)
+            }
+        }
+
+        impl<'a, T> $trait<T> for &'a Matrix<T>
+        where
+            T: $trait<Output = T> + Copy,
+        {
+            type Output = Matrix<T>;
+
+            fn $method(self, scalar: T) -> Matrix<T> {
+                self.map(|a| a.$method(scalar))
+            }
+        }
+
+        impl<T> $trait<T> for Matrix<T>
+        where
+            T: $trait<Output = T> + Copy,
+        {
+            type Output = Matrix<T>;
+
+            fn $method(self, scalar: T) -> Matrix<T> {
+                (&self).$method(scalar)
+            }
+        }
+    };
+}
+
+impl_op!(Add, add);
+impl_op!(Sub, sub);
+impl_op!(Mul, mul);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::matrix::testing::counting;
+
+    #[test]
+    fn test_map_and_zip() {
+        let m = counting();
+        let odd = m.map(|v| v % 2 == 1);
+        assert_eq!(
+            format!("{:?}", odd),
+            "[[true, false, true]\n [false, true, false]]"
+        );
+
+        let labels = m.zip_with(&odd, |v, o| if *o { *v } else { -v });
+        assert_eq!(format!("{:?}", labels), "[[1, -2, 3]\n [-4, 5, -6]]");
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_zip_different_sizes() {
+        let a = Matrix::new(2, 3, 0);
+        let b = Matrix::new(3, 2, 0);
+        a.zip_with(&b, |x, y| x + y);
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let m = counting();
+        assert_eq!(format!("{:?}", &m + 1), "[[2, 3, 4]\n [5, 6, 7]]");
+        assert_eq!(format!("{:?}", &m * 2), "[[2, 4, 6]\n [8, 10, 12]]");
+        assert_eq!(format!("{:?}", &m - &m), "[[0, 0, 0]\n [0, 0, 0]]");
+        assert_eq!(format!("{:?}", &m * &m), "[[1, 4, 9]\n [16, 25, 36]]");
+        assert_eq!(
+            format!("{:?}", counting() + counting() - 1),
+            "[[1, 3, 5]\n [7, 9, 11]]"
+        );
+    }
+
+    #[test]
+    fn test_reductions() {
+        let mut m = counting();
+        assert_eq!(m.sum(), 21);
+        assert_eq!(m.count_where(|v| *v > 2), 4);
+        assert_eq!(m.max_by_key(|v| *v), Some((RowCol::new(1, 2), &6)));
+        assert_eq!(m.max_by_key(|v| v % 3), Some((RowCol::new(0, 1), &2)));
+
+        m.fill(7);
+        assert_eq!(m.sum(), 42);
+        assert_eq!(m.max_by_key(|v| *v), Some((RowCol::new(0, 0), &7)));
+
+        let empty: Matrix<i32> = Matrix::new(0, 0, 0);
+        assert_eq!(empty.max_by_key(|v| *v), None);
+        assert_eq!(empty.sum(), 0);
+    }
+}
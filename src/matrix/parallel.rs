@@ -0,0 +1,78 @@
+// Row-wise and cell-wise operations that run on the rayon thread pool. Only
+// built with the `parallel` feature.
+
+use super::{Matrix, RowCol};
+use rayon::prelude::*;
+
+impl<T> Matrix<T>
+where
+    T: Send + Sync,
+{
+    /// Mutable rows, top to bottom, that can be filled in parallel
+    pub fn par_rows_mut(&mut self) -> impl IndexedParallelIterator<Item = &mut [T]> {
+        // chunks of zero would panic, and a zero width matrix has no cells
+        // to hand out anyway
+        let width = self.width.max(1);
+        self.buf.par_chunks_mut(width)
+    }
+
+    /// Applies `f` to every cell in parallel
+    pub fn par_map<U, F>(&self, f: F) -> Matrix<U>
+    where
+        U: Send,
+        F: Fn(&T) -> U + Sync + Send,
+    {
+        Matrix {
+            buf: self.buf.par_iter().map(f).collect(),
+            height: self.height,
+            width: self.width,
+        }
+    }
+
+    /// Every cell together with its position, in reading order
+    pub fn par_indexed_iter(&self) -> impl IndexedParallelIterator<Item = (RowCol, &T)> {
+        let width = self.width;
+        self.buf
+            .par_iter()
+            .enumerate()
+            .map(move |(i, v)| (RowCol::new(i / width, i % width), v))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_par_rows_mut() {
+        let mut m = Matrix::new(3, 4, 0);
+        m.par_rows_mut().enumerate().for_each(|(i, row)| {
+            for (j, v) in row.iter_mut().enumerate() {
+                *v = i * 10 + j;
+            }
+        });
+
+        assert!(m == Matrix::from_fn(3, 4, |s| s.row * 10 + s.col));
+        assert_eq!(Matrix::new(0, 0, 0).par_rows_mut().count(), 0);
+    }
+
+    #[test]
+    fn test_par_map() {
+        let m = Matrix::from_fn(5, 7, |s| s.row * s.col);
+        assert!(m.par_map(|v| v + 1) == m.map(|v| v + 1));
+    }
+
+    #[test]
+    fn test_par_indexed_iter() {
+        let m = Matrix::from_fn(4, 3, |s| s.row + s.col);
+
+        let cells: Vec<(RowCol, usize)> = m.par_indexed_iter().map(|(s, v)| (s, *v)).collect();
+        assert_eq!(cells.len(), 12);
+        assert_eq!(cells[0], (RowCol::new(0, 0), 0));
+        assert_eq!(cells[5], (RowCol::new(1, 2), 3));
+        assert!(cells.iter().all(|(s, v)| s.row + s.col == *v));
+
+        let total: usize = m.par_indexed_iter().map(|(_, v)| *v).sum();
+        assert_eq!(total, m.sum());
+    }
+}
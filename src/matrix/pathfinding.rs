@@ -0,0 +1,360 @@
+// Shortest paths over a matrix, moving between orthogonal neighbors.
+//
+// Of the equally cheap paths, the ones with the fewest steps are preferred,
+// and remaining ties are broken in reading order (top to bottom, then left
+// to right) so that the same grid always produces the same answer: the path
+// whose first step comes first in reading order wins, then the one whose
+// second step does, and so on.
+
+use super::{Matrix, RowCol};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+
+#[derive(Debug, PartialEq)]
+pub struct Path {
+    pub cost: u32,
+    pub steps: Vec<RowCol>, // includes both the start and the goal
+}
+
+/// Number of steps from `start` to every cell that can be reached from it,
+/// moving only through cells for which `passable` holds
+pub fn bfs<T, F>(m: &Matrix<T>, start: RowCol, passable: F) -> Matrix<Option<usize>>
+where
+    F: Fn(&T) -> bool,
+{
+    let mut distances = Matrix::new(m.height, m.width, None);
+    let mut queue = VecDeque::new();
+
+    distances[start] = Some(0);
+    queue.push_back(start);
+
+    while let Some(pos) = queue.pop_front() {
+        let d = distances[pos].unwrap();
+        for next in m.neighbors(pos) {
+            if distances[next].is_none() && passable(&m[next]) {
+                distances[next] = Some(d + 1);
+                queue.push_back(next);
+            }
+        }
+    }
+
+    distances
+}
+
+/// The closest cell in a distance map that satisfies `is_target`, picking the
+/// first one in reading order when several are equally close
+pub fn nearest<F>(distances: &Matrix<Option<usize>>, is_target: F) -> Option<(RowCol, usize)>
+where
+    F: Fn(RowCol) -> bool,
+{
+    let mut best = None;
+
+    // scanning in reading order and only replacing on a strictly smaller
+    // distance keeps the earliest of the ties
+    for i in 0..distances.height {
+        for j in 0..distances.width {
+            let s = RowCol::new(i, j);
+            if let Some(d) = distances[s] {
+                let closer = match best {
+                    Some((_, best_d)) => d < best_d,
+                    None => true,
+                };
+                if closer && is_target(s) {
+                    best = Some((s, d));
+                }
+            }
+        }
+    }
+
+    best
+}
+
+/// Cheapest path from `start` to `goal`, taking as few steps as possible
+/// when several are equally cheap. `cost` gives the price of stepping onto a
+/// cell, or `None` if the cell can't be entered.
+pub fn dijkstra<T, F>(m: &Matrix<T>, start: RowCol, goal: RowCol, cost: F) -> Option<Path>
+where
+    F: Fn(&T) -> Option<u32>,
+{
+    search(m, start, goal, cost, |_| 0)
+}
+
+/// Same as `dijkstra`, but guided by the manhattan distance between the
+/// start and the goal. This only finds the cheapest path when every step
+/// costs at least 1, although it always finds some path if there is one.
+pub fn astar<T, F>(m: &Matrix<T>, start: RowCol, goal: RowCol, cost: F) -> Option<Path>
+where
+    F: Fn(&T) -> Option<u32>,
+{
+    search(m, start, goal, cost, |pos| manhattan(pos, start))
+}
+
+fn manhattan(a: RowCol, b: RowCol) -> u32 {
+    let di = (a.row as isize - b.row as isize).abs();
+    let dj = (a.col as isize - b.col as isize).abs();
+    (di + dj) as u32
+}
+
+fn search<T, F, H>(
+    m: &Matrix<T>,
+    start: RowCol,
+    goal: RowCol,
+    cost: F,
+    heuristic: H,
+) -> Option<Path>
+where
+    F: Fn(&T) -> Option<u32>,
+    H: Fn(RowCol) -> u32,
+{
+    let remaining = costs_to(m, start, goal, &cost, heuristic);
+    let (total, _) = remaining[start]?;
+
+    // walk forward from the start, always taking the earliest neighbor that
+    // is still on a best path, so the steps are compared in the order
+    // they're taken rather than backwards from the goal. Every step leaves
+    // one step fewer to go, so zero cost cells can't send it round in
+    // circles.
+    let mut steps = vec![start];
+    let mut pos = start;
+    while pos != goal {
+        let (left, left_steps) = remaining[pos].unwrap();
+
+        // neighbors come in reading order, and one of them is always on a
+        // best path
+        pos = m
+            .neighbors(pos)
+            .find(|&next| match (cost(&m[next]), remaining[next]) {
+                (Some(step), Some((after, after_steps))) => {
+                    step + after == left && after_steps + 1 == left_steps
+                }
+                _ => false,
+            })
+            .unwrap();
+        steps.push(pos);
+    }
+
+    Some(Path { cost: total, steps })
+}
+
+/// The cost and number of steps of the best path from each cell to `goal`,
+/// where a cheaper path is better and then one with fewer steps. The search
+/// runs backwards from the goal until this is known exactly for every cell
+/// on a best path from `start`, as long as `heuristic` never overestimates
+/// the cost from `start`. Otherwise every cell that is kept still has a path
+/// to the goal of that cost and length, just not necessarily the best one.
+fn costs_to<T, F, H>(
+    m: &Matrix<T>,
+    start: RowCol,
+    goal: RowCol,
+    cost: &F,
+    heuristic: H,
+) -> Matrix<Option<(u32, usize)>>
+where
+    F: Fn(&T) -> Option<u32>,
+    H: Fn(RowCol) -> u32,
+{
+    let mut best: Matrix<Option<(u32, usize)>> = Matrix::new(m.height, m.width, None);
+    let mut done = Matrix::new(m.height, m.width, false);
+    let mut heap = BinaryHeap::new();
+
+    best[goal] = Some((0, 0));
+    heap.push(Reverse((heuristic(goal), 0, goal)));
+
+    while let Some(Reverse((estimate, steps, pos))) = heap.pop() {
+        if done[pos] {
+            continue;
+        }
+
+        // nothing estimated to be worse than the start can be on one of its
+        // best paths
+        if done[start] && (estimate, steps) > best[start].unwrap() {
+            break;
+        }
+        done[pos] = true;
+
+        // stepping from a neighbor onto this cell costs what the cell does,
+        // and a cell that can't be entered can't be passed through either
+        let step = match cost(&m[pos]) {
+            Some(step) => step,
+            None => continue,
+        };
+
+        let (so_far, steps) = best[pos].unwrap();
+        let total = (so_far + step, steps + 1);
+        // settled cells are final, so that the walk can always follow a
+        // cell's value back to the neighbor it came from
+        for prev in m.neighbors(pos) {
+            if !done[prev] && best[prev].is_none_or(|b| total < b) {
+                best[prev] = Some(total);
+                heap.push(Reverse((total.0 + heuristic(prev), total.1, prev)));
+            }
+        }
+    }
+
+    // cells that weren't settled may still have a better path
+    Matrix::from_fn(m.height, m.width, |s| if done[s] { best[s] } else { None })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::matrix::testing::{grid, tuples};
+
+    fn open(c: &char) -> Option<u32> {
+        if *c == '#' {
+            None
+        } else {
+            Some(1)
+        }
+    }
+
+    #[test]
+    fn test_bfs() {
+        let m = grid(
+            "#######
+#.....#
+#.###.#
+#...#.#
+#######",
+        );
+
+        let distances = bfs(&m, RowCol::new(1, 1), |c| *c != '#');
+        assert_eq!(distances[RowCol::new(1, 1)], Some(0));
+        assert_eq!(distances[RowCol::new(1, 5)], Some(4));
+        assert_eq!(distances[RowCol::new(3, 5)], Some(6));
+        assert_eq!(distances[RowCol::new(3, 3)], Some(4));
+        assert_eq!(distances[RowCol::new(0, 0)], None);
+        assert_eq!(distances[RowCol::new(2, 2)], None);
+    }
+
+    #[test]
+    fn test_nearest_reading_order() {
+        let m = grid(
+            "#####
+#...#
+#...#
+#...#
+#####",
+        );
+
+        // (1, 2), (2, 1), (2, 3) and (3, 2) are all one step from the middle
+        let distances = bfs(&m, RowCol::new(2, 2), |c| *c != '#');
+        let middle = RowCol::new(2, 2);
+        let found = nearest(&distances, |pos| pos != middle);
+        assert_eq!(found, Some((RowCol::new(1, 2), 1)));
+
+        let found = nearest(&distances, |pos| pos.row == 3);
+        assert_eq!(found, Some((RowCol::new(3, 2), 1)));
+
+        assert_eq!(nearest(&distances, |_| false), None);
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        let m = grid(
+            "#######
+#.....#
+#.###.#
+#...#.#
+#######",
+        );
+
+        let path = dijkstra(&m, RowCol::new(1, 1), RowCol::new(3, 5), open).unwrap();
+        assert_eq!(path.cost, 6);
+        assert_eq!(path.steps.first(), Some(&RowCol::new(1, 1)));
+        assert_eq!(path.steps.last(), Some(&RowCol::new(3, 5)));
+        assert_eq!(path.steps.len(), 7);
+
+        assert_eq!(
+            dijkstra(&m, RowCol::new(1, 1), RowCol::new(0, 0), open),
+            None
+        );
+        assert_eq!(
+            dijkstra(&m, RowCol::new(1, 1), RowCol::new(1, 1), open),
+            Some(Path {
+                cost: 0,
+                steps: vec![RowCol::new(1, 1)]
+            })
+        );
+    }
+
+    #[test]
+    fn test_weighted_paths() {
+        // going through the 9 is shorter but more expensive than going round
+        let mut m = Matrix::new(3, 3, 1);
+        m[RowCol::new(1, 1)] = 9;
+        m[RowCol::new(0, 1)] = 9;
+
+        let cost = |c: &u32| Some(*c);
+        let path = dijkstra(&m, RowCol::new(0, 0), RowCol::new(0, 2), cost).unwrap();
+        assert_eq!(path.cost, 6);
+        assert_eq!(
+            tuples(path.steps.clone()),
+            vec![(0, 0), (1, 0), (2, 0), (2, 1), (2, 2), (1, 2), (0, 2)]
+        );
+
+        let (start, goal) = (RowCol::new(0, 0), RowCol::new(0, 2));
+        assert_eq!(astar(&m, start, goal, cost), Some(path));
+    }
+
+    #[test]
+    fn test_path_tie_breaking() {
+        let m = Matrix::new(3, 3, '.');
+
+        // every monotone path is equally short, the one that stays earliest
+        // in reading order wins
+        let (start, goal) = (RowCol::new(0, 0), RowCol::new(2, 2));
+        let expected = vec![(0, 0), (0, 1), (0, 2), (1, 2), (2, 2)];
+        assert_eq!(
+            tuples(dijkstra(&m, start, goal, open).unwrap().steps),
+            expected
+        );
+        assert_eq!(
+            tuples(astar(&m, start, goal, open).unwrap().steps),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_zero_cost_paths() {
+        let cost = |c: &u32| Some(*c);
+
+        let m = Matrix::new(1, 3, 0);
+        let (start, goal) = (RowCol::new(0, 0), RowCol::new(0, 2));
+        let path = dijkstra(&m, start, goal, cost).unwrap();
+        assert_eq!(path.cost, 0);
+        assert_eq!(tuples(path.steps.clone()), vec![(0, 0), (0, 1), (0, 2)]);
+        assert_eq!(astar(&m, start, goal, cost), Some(path));
+
+        // the only free way there goes round along the bottom
+        let mut m = Matrix::new(3, 3, 0);
+        m[RowCol::new(0, 1)] = 1;
+        m[RowCol::new(1, 1)] = 1;
+        let (start, goal) = (RowCol::new(2, 0), RowCol::new(0, 2));
+        let path = dijkstra(&m, start, goal, cost).unwrap();
+        assert_eq!(path.cost, 0);
+        assert_eq!(path.steps.len(), 5);
+        assert_eq!(tuples(astar(&m, start, goal, cost).unwrap().steps).len(), 5);
+    }
+
+    #[test]
+    fn test_path_tie_breaking_first_step() {
+        // arriving at the goal from the earliest cell would start by going
+        // down, but going right first is just as short
+        let m = grid(
+            "#...##.
+....#..
+.#.....
+.......
+..#..##
+...##..
+...#...",
+        );
+
+        let (start, goal) = (RowCol::new(0, 1), RowCol::new(5, 1));
+        let path = dijkstra(&m, start, goal, open).unwrap();
+        assert_eq!(path.cost, 7);
+        assert_eq!(path.steps[1], RowCol::new(0, 2));
+        assert_eq!(astar(&m, start, goal, open), Some(path));
+    }
+}
@@ -0,0 +1,178 @@
+// Finding connected regions in a matrix. Two orthogonal neighbors belong to
+// the same region when the caller's key function gives them the same key;
+// cells without a key don't belong to any region.
+
+use super::{Bounds, Matrix, RowCol};
+use std::collections::VecDeque;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Region {
+    pub size: usize,
+    pub bounds: Bounds,
+    pub touches_border: bool, // whether any cell lies on the edge of the matrix
+}
+
+#[derive(Debug)]
+pub struct Regions {
+    pub labels: Matrix<Option<usize>>, // index into `regions` for every cell
+    pub regions: Vec<Region>,
+}
+
+/// Labels the single region containing `start`
+pub fn flood_fill<T, K, F>(m: &Matrix<T>, start: RowCol, key: F) -> Regions
+where
+    K: PartialEq,
+    F: Fn(&T) -> Option<K>,
+{
+    let mut result = Regions {
+        labels: Matrix::new(m.height, m.width, None),
+        regions: vec![],
+    };
+
+    if let Some(k) = key(&m[start]) {
+        let region = fill(m, &key, &k, start, 0, &mut result.labels);
+        result.regions.push(region);
+    }
+
+    result
+}
+
+/// Labels every region in the matrix. Region ids are handed out in the
+/// reading order of each region's first cell.
+pub fn label_components<T, K, F>(m: &Matrix<T>, key: F) -> Regions
+where
+    K: PartialEq,
+    F: Fn(&T) -> Option<K>,
+{
+    let mut result = Regions {
+        labels: Matrix::new(m.height, m.width, None),
+        regions: vec![],
+    };
+
+    for i in 0..m.height {
+        for j in 0..m.width {
+            let s = RowCol::new(i, j);
+            if result.labels[s].is_some() {
+                continue;
+            }
+
+            if let Some(k) = key(&m[s]) {
+                let id = result.regions.len();
+                let region = fill(m, &key, &k, s, id, &mut result.labels);
+                result.regions.push(region);
+            }
+        }
+    }
+
+    result
+}
+
+/// Breadth first fill of every cell reachable from `start` with key `k`
+fn fill<T, K, F>(
+    m: &Matrix<T>,
+    key: &F,
+    k: &K,
+    start: RowCol,
+    id: usize,
+    labels: &mut Matrix<Option<usize>>,
+) -> Region
+where
+    K: PartialEq,
+    F: Fn(&T) -> Option<K>,
+{
+    let as_signed = |pos: RowCol| RowCol::new(pos.row as isize, pos.col as isize);
+
+    let mut region = Region {
+        size: 0,
+        bounds: Bounds::at(as_signed(start)),
+        touches_border: false,
+    };
+    let mut queue = VecDeque::new();
+
+    labels[start] = Some(id);
+    queue.push_back(start);
+
+    while let Some(pos) = queue.pop_front() {
+        region.size += 1;
+        region.bounds.include(as_signed(pos));
+        region.touches_border |=
+            pos.row == 0 || pos.col == 0 || pos.row == m.height - 1 || pos.col == m.width - 1;
+
+        for next in m.neighbors(pos) {
+            if labels[next].is_none() && key(&m[next]).as_ref() == Some(k) {
+                labels[next] = Some(id);
+                queue.push_back(next);
+            }
+        }
+    }
+
+    region
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::matrix::testing::grid;
+
+    fn walls(c: &char) -> Option<char> {
+        if *c == '#' {
+            None
+        } else {
+            Some(*c)
+        }
+    }
+
+    #[test]
+    fn test_label_components() {
+        let m = grid(
+            "aa#bb
+a##bb
+##c#b
+cc#.b",
+        );
+
+        let result = label_components(&m, walls);
+        assert_eq!(result.regions.len(), 5);
+
+        // a, b, lone c, bottom left c, .
+        assert_eq!(result.labels[RowCol::new(0, 0)], Some(0));
+        assert_eq!(result.labels[RowCol::new(1, 0)], Some(0));
+        assert_eq!(result.labels[RowCol::new(0, 3)], Some(1));
+        assert_eq!(result.labels[RowCol::new(3, 4)], Some(1));
+        assert_eq!(result.labels[RowCol::new(2, 2)], Some(2));
+        assert_eq!(result.labels[RowCol::new(3, 0)], Some(3));
+        assert_eq!(result.labels[RowCol::new(3, 3)], Some(4));
+        assert_eq!(result.labels[RowCol::new(0, 2)], None);
+
+        let sizes: Vec<_> = result.regions.iter().map(|r| r.size).collect();
+        assert_eq!(sizes, vec![3, 6, 1, 2, 1]);
+
+        assert_eq!(
+            result.regions[1].bounds,
+            Bounds {
+                min: RowCol::new(0, 3),
+                max: RowCol::new(3, 4)
+            }
+        );
+        assert!(result.regions[1].touches_border);
+        assert!(!result.regions[2].touches_border);
+    }
+
+    #[test]
+    fn test_flood_fill() {
+        let m = grid(
+            "...#.
+.#.#.
+.#...",
+        );
+
+        let result = flood_fill(&m, RowCol::new(0, 0), walls);
+        assert_eq!(result.regions.len(), 1);
+        assert_eq!(result.regions[0].size, 11);
+        assert_eq!(result.labels[RowCol::new(0, 4)], Some(0));
+        assert_eq!(result.labels[RowCol::new(1, 1)], None);
+
+        let result = flood_fill(&m, RowCol::new(0, 3), walls);
+        assert!(result.regions.is_empty());
+    }
+}
@@ -0,0 +1,118 @@
+// A grid that only stores the positions holding a value, for coordinate
+// spaces that are far too large (or unbounded) to allocate densely
+
+use super::{Bounds, Cells, Grid, OffsetMatrix, RowCol};
+use std::collections::HashMap;
+
+#[derive(Debug, Default)]
+pub struct SparseGrid<T> {
+    cells: HashMap<RowCol<isize>, T>,
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> SparseGrid<T> {
+        SparseGrid {
+            cells: HashMap::new(),
+        }
+    }
+
+    /// Removes and returns the value at a position
+    pub fn remove(&mut self, pos: RowCol<isize>) -> Option<T> {
+        self.cells.remove(&pos)
+    }
+
+    /// The number of positions holding a value
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+}
+
+impl<T> SparseGrid<T>
+where
+    T: Copy,
+{
+    /// Copies the grid into dense storage, filling the gaps with `default`
+    pub fn to_dense(&self, default: T) -> OffsetMatrix<T> {
+        let mut m = OffsetMatrix::new(default);
+        for (pos, v) in &self.cells {
+            m[*pos] = *v;
+        }
+
+        m
+    }
+}
+
+impl<T> Grid<T> for SparseGrid<T> {
+    fn get(&self, pos: RowCol<isize>) -> Option<&T> {
+        self.cells.get(&pos)
+    }
+
+    fn set(&mut self, pos: RowCol<isize>, value: T) {
+        self.cells.insert(pos, value);
+    }
+
+    fn bounds(&self) -> Option<Bounds> {
+        let mut positions = self.cells.keys();
+        let mut bounds = Bounds::at(*positions.next()?);
+        for pos in positions {
+            bounds.include(*pos);
+        }
+
+        Some(bounds)
+    }
+
+    /// Iterates over the values in no particular order
+    fn cells(&self) -> Cells<'_, T> {
+        Box::new(self.cells.iter().map(|(pos, v)| (*pos, v)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_sparse_grid() {
+        let mut g = SparseGrid::new();
+        assert_eq!(g.bounds(), None);
+        assert_eq!(g.render(|_| '#'), "");
+
+        g.set(RowCol::new(-10005, 20416), 1);
+        g.set(RowCol::new(10000, -20000), 2);
+
+        assert_eq!(g.len(), 2);
+        assert_eq!(g.get(RowCol::new(-10005, 20416)), Some(&1));
+        assert_eq!(g.get(RowCol::new(0, 0)), None);
+        assert_eq!(
+            g.bounds(),
+            Some(Bounds {
+                min: RowCol::new(-10005, -20000),
+                max: RowCol::new(10000, 20416)
+            })
+        );
+
+        assert_eq!(g.remove(RowCol::new(10000, -20000)), Some(2));
+        assert_eq!(g.bounds(), Some(Bounds::at(RowCol::new(-10005, 20416))));
+    }
+
+    #[test]
+    fn test_sparse_grid_render() {
+        let mut g = SparseGrid::new();
+        g.set(RowCol::new(3, -1), '#');
+        g.set(RowCol::new(4, 1), '#');
+
+        assert_eq!(
+            g.render(|c| if c.is_some() { '#' } else { '.' }),
+            "#..\n..#"
+        );
+
+        let dense = g.to_dense('.');
+        assert_eq!(dense[RowCol::new(3, 0)], '.');
+        assert_eq!(dense[RowCol::new(4, 1)], '#');
+        assert_eq!(dense.bounds(), g.bounds());
+    }
+}
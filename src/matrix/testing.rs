@@ -0,0 +1,22 @@
+// Fixtures shared by the tests of the matrix modules
+
+use super::{Matrix, RowCol};
+
+/// 2x3 matrix with the values 1 to 6
+pub fn counting() -> Matrix<i32> {
+    Matrix::from_fn(2, 3, |s| (s.row * 3 + s.col + 1) as i32)
+}
+
+/// A matrix of characters with one row per line, which must all be as long
+pub fn grid(s: &str) -> Matrix<char> {
+    let lines: Vec<&str> = s.lines().collect();
+    let width = lines.first().map_or(0, |line| line.chars().count());
+    let cells: Vec<char> = lines.iter().flat_map(|line| line.chars()).collect();
+
+    Matrix::from_vec(lines.len(), width, cells).unwrap()
+}
+
+/// Positions as (row, col) tuples, to keep the expectations short
+pub fn tuples<I: IntoIterator<Item = RowCol>>(positions: I) -> Vec<(usize, usize)> {
+    positions.into_iter().map(|s| (s.row, s.col)).collect()
+}
@@ -0,0 +1,98 @@
+// Reshaping a matrix: transposition, quarter turns and mirroring.
+// Each of these returns a new matrix and leaves the original alone.
+
+use super::{Matrix, RowCol};
+
+impl<T> Matrix<T>
+where
+    T: Clone,
+{
+    /// Builds a `height` x `width` matrix where each cell is picked from self
+    fn remap<F>(&self, height: usize, width: usize, source: F) -> Matrix<T>
+    where
+        F: Fn(RowCol) -> RowCol,
+    {
+        Matrix::from_fn(height, width, |s| self[source(s)].clone())
+    }
+
+    /// Swaps rows and columns
+    pub fn transpose(&self) -> Matrix<T> {
+        self.remap(self.width, self.height, |s| RowCol::new(s.col, s.row))
+    }
+
+    /// Rotates a quarter turn clockwise
+    pub fn rotate_cw(&self) -> Matrix<T> {
+        let height = self.height;
+        self.remap(self.width, self.height, |s| {
+            RowCol::new(height - 1 - s.col, s.row)
+        })
+    }
+
+    /// Rotates a quarter turn counter-clockwise
+    pub fn rotate_ccw(&self) -> Matrix<T> {
+        let width = self.width;
+        self.remap(self.width, self.height, |s| {
+            RowCol::new(s.col, width - 1 - s.row)
+        })
+    }
+
+    /// Mirrors left to right, so that the first column becomes the last
+    pub fn flip_horizontal(&self) -> Matrix<T> {
+        let width = self.width;
+        self.remap(self.height, self.width, |s| {
+            RowCol::new(s.row, width - 1 - s.col)
+        })
+    }
+
+    /// Mirrors top to bottom, so that the first row becomes the last
+    pub fn flip_vertical(&self) -> Matrix<T> {
+        let height = self.height;
+        self.remap(self.height, self.width, |s| {
+            RowCol::new(height - 1 - s.row, s.col)
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::matrix::testing::counting;
+
+    #[test]
+    fn test_transpose() {
+        let m = counting().transpose();
+        assert_eq!((m.height, m.width), (3, 2));
+        assert_eq!(format!("{:?}", m), "[[1, 4]\n [2, 5]\n [3, 6]]");
+    }
+
+    #[test]
+    fn test_rotate() {
+        assert_eq!(
+            format!("{:?}", counting().rotate_cw()),
+            "[[4, 1]\n [5, 2]\n [6, 3]]"
+        );
+        assert_eq!(
+            format!("{:?}", counting().rotate_ccw()),
+            "[[3, 6]\n [2, 5]\n [1, 4]]"
+        );
+
+        let m = counting();
+        let full_turn = m.rotate_cw().rotate_cw().rotate_cw().rotate_cw();
+        assert_eq!(format!("{:?}", full_turn), format!("{:?}", m));
+        assert_eq!(
+            format!("{:?}", m.rotate_cw().rotate_ccw()),
+            format!("{:?}", m)
+        );
+    }
+
+    #[test]
+    fn test_flip() {
+        assert_eq!(
+            format!("{:?}", counting().flip_horizontal()),
+            "[[3, 2, 1]\n [6, 5, 4]]"
+        );
+        assert_eq!(
+            format!("{:?}", counting().flip_vertical()),
+            "[[4, 5, 6]\n [1, 2, 3]]"
+        );
+    }
+}
@@ -0,0 +1,179 @@
+// Borrowed rectangular windows into a matrix. A view shares the storage
+// of the matrix it was taken from, so taking one doesn't copy anything.
+
+use super::{Matrix, RowCol};
+use std::ops::{Index, IndexMut};
+
+pub struct SubMatrix<'a, T> {
+    matrix: &'a Matrix<T>,
+    origin: RowCol,
+    pub height: usize,
+    pub width: usize,
+}
+
+pub struct SubMatrixMut<'a, T> {
+    matrix: &'a mut Matrix<T>,
+    origin: RowCol,
+    pub height: usize,
+    pub width: usize,
+}
+
+impl<T> Matrix<T> {
+    /// A `height` x `width` window whose top left corner is at `origin`
+    pub fn view(&self, origin: RowCol, height: usize, width: usize) -> SubMatrix<'_, T> {
+        assert!(origin.row + height <= self.height);
+        assert!(origin.col + width <= self.width);
+
+        SubMatrix {
+            matrix: self,
+            origin,
+            height,
+            width,
+        }
+    }
+
+    /// Same as `view`, but the window can be written through
+    pub fn view_mut(&mut self, origin: RowCol, height: usize, width: usize) -> SubMatrixMut<'_, T> {
+        assert!(origin.row + height <= self.height);
+        assert!(origin.col + width <= self.width);
+
+        SubMatrixMut {
+            matrix: self,
+            origin,
+            height,
+            width,
+        }
+    }
+}
+
+/// Translates a position in a window to one in the underlying matrix
+fn to_parent(origin: RowCol, height: usize, width: usize, s: RowCol) -> RowCol {
+    assert!(s.row < height);
+    assert!(s.col < width);
+
+    RowCol::new(origin.row + s.row, origin.col + s.col)
+}
+
+impl<'a, T> SubMatrix<'a, T> {
+    /// A window into this window, positioned relative to it
+    pub fn view(&self, origin: RowCol, height: usize, width: usize) -> SubMatrix<'a, T> {
+        assert!(origin.row + height <= self.height);
+        assert!(origin.col + width <= self.width);
+
+        SubMatrix {
+            matrix: self.matrix,
+            origin: RowCol::new(self.origin.row + origin.row, self.origin.col + origin.col),
+            height,
+            width,
+        }
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = Vec<&'a T>> + '_ {
+        (0..self.height).map(move |y| {
+            (0..self.width)
+                .map(|x| &self.matrix[RowCol::new(self.origin.row + y, self.origin.col + x)])
+                .collect()
+        })
+    }
+}
+
+impl<'a, T> SubMatrix<'a, T>
+where
+    T: Clone,
+{
+    /// Copies the window out into a matrix of its own
+    pub fn to_matrix(&self) -> Matrix<T> {
+        Matrix {
+            buf: self.rows().flat_map(|r| r.into_iter().cloned()).collect(),
+            height: self.height,
+            width: self.width,
+        }
+    }
+}
+
+impl<'a, T> SubMatrixMut<'a, T> {
+    pub fn rows(&self) -> impl Iterator<Item = Vec<&T>> {
+        (0..self.height).map(move |y| (0..self.width).map(|x| &self[RowCol::new(y, x)]).collect())
+    }
+}
+
+/// Views are indexed relative to their top left corner, by `RowCol` or `Pos`
+impl<'a, T, S> Index<S> for SubMatrix<'a, T>
+where
+    S: Into<RowCol>,
+{
+    type Output = T;
+
+    fn index(&self, s: S) -> &T {
+        &self.matrix[to_parent(self.origin, self.height, self.width, s.into())]
+    }
+}
+
+impl<'a, T, S> Index<S> for SubMatrixMut<'a, T>
+where
+    S: Into<RowCol>,
+{
+    type Output = T;
+
+    fn index(&self, s: S) -> &T {
+        &self.matrix[to_parent(self.origin, self.height, self.width, s.into())]
+    }
+}
+
+impl<'a, T, S> IndexMut<S> for SubMatrixMut<'a, T>
+where
+    S: Into<RowCol>,
+{
+    fn index_mut(&mut self, s: S) -> &mut T {
+        &mut self.matrix[to_parent(self.origin, self.height, self.width, s.into())]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::matrix::Pos;
+
+    #[test]
+    fn test_view() {
+        let mut m = Matrix::new(4, 5, 0);
+        for i in 0..4 {
+            for j in 0..5 {
+                m[RowCol::new(i, j)] = i * 10 + j;
+            }
+        }
+
+        let v = m.view(RowCol::new(1, 2), 2, 3);
+        assert_eq!(v[RowCol::new(0, 0)], 12);
+        assert_eq!(v[RowCol::new(1, 2)], 24);
+        assert_eq!(v[Pos::new(2, 1)], 24);
+
+        let total: usize = v.rows().flat_map(|r| r.into_iter()).sum();
+        assert_eq!(total, 12 + 13 + 14 + 22 + 23 + 24);
+
+        let inner = v.view(RowCol::new(1, 1), 1, 2);
+        assert_eq!(inner[RowCol::new(0, 1)], 24);
+        assert_eq!(format!("{:?}", inner.to_matrix()), "[[23, 24]]");
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_view_out_of_bounds() {
+        let m = Matrix::new(3, 3, 0);
+        let v = m.view(RowCol::new(1, 1), 2, 2);
+        let _ = v[RowCol::new(2, 0)];
+    }
+
+    #[test]
+    fn test_view_mut() {
+        let mut m = Matrix::new(3, 3, 0);
+        {
+            let mut v = m.view_mut(RowCol::new(1, 1), 2, 2);
+            v[RowCol::new(0, 0)] = 1;
+            v[Pos::new(1, 1)] = 2;
+            assert_eq!(v.rows().count(), 2);
+        }
+
+        assert_eq!(format!("{:?}", m), "[[0, 0, 0]\n [0, 1, 0]\n [0, 0, 2]]");
+    }
+}
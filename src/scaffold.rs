@@ -1,5 +1,5 @@
-/// helper code to generate a scaffolding for solution
-/// call with 4
+// helper code to generate a scaffolding for solution
+// call with 4
 
 #[macro_use]
 extern crate serde_derive;
@@ -17,13 +17,11 @@ struct CargoTargets {
 #[derive(Debug, Deserialize)]
 struct Bin {
     name: String,
-    path: String,
 }
 
 fn main() -> std::io::Result<()> {
     let day = env::args()
-        .skip(1)
-        .next()
+        .nth(1)
         .expect("Usage: cargo run --scaffold 05");
 
     create_input_file(&day)?;